@@ -0,0 +1,119 @@
+//! Genesis presets for the DEX parachain.
+//!
+//! The same as the ones of the `parachain` runtime. Pools aren't created in genesis, they need
+//! liquidity that's better chosen by whoever uses the chain.
+
+use crate::{
+	configs::xcm_config::TreasuryAccount, AccountId, BalancesConfig, CollatorSelectionConfig,
+	ForeignAssetsConfig, ParachainInfoConfig, PolkadotXcmConfig, RuntimeGenesisConfig,
+	SessionConfig, SessionKeys, SudoConfig, EXISTENTIAL_DEPOSIT, UNITS,
+};
+
+use alloc::{vec, vec::Vec};
+use cumulus_primitives_core::ParaId;
+use frame_support::build_struct_json_patch;
+use serde_json::Value;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_genesis_builder::{PresetId, DEV_RUNTIME_PRESET};
+use sp_keyring::Sr25519Keyring;
+use xcm::latest::prelude::*;
+
+/// The preset used by the emulated network in the `emulator` crate.
+pub const EMULATOR_RUNTIME_PRESET: &str = "emulator";
+
+/// The para id every preset uses.
+pub const PARACHAIN_ID: u32 = crate::configs::xcm_config::DEX_PARA_ID;
+
+/// The amount of the native token every well-known account starts with.
+pub const ENDOWMENT: u128 = 1_000 * UNITS;
+
+/// The XCM version we assume other chains speak until they tell us otherwise.
+const SAFE_XCM_VERSION: u32 = xcm::prelude::XCM_VERSION;
+
+/// Number of decimals of `WND`, the relay chain token.
+const WND_DECIMALS: u8 = 12;
+
+fn genesis(
+	invulnerables: Vec<(AccountId, AuraId)>,
+	endowed_accounts: Vec<AccountId>,
+	root: AccountId,
+	id: ParaId,
+) -> Value {
+	build_struct_json_patch!(RuntimeGenesisConfig {
+		balances: BalancesConfig {
+			balances: endowed_accounts
+				.iter()
+				.cloned()
+				.map(|k| (k, ENDOWMENT))
+				// Fees paid in foreign tokens go to the treasury, which has to exist to hold them.
+				.chain([(TreasuryAccount::get(), EXISTENTIAL_DEPOSIT)])
+				.collect::<Vec<_>>(),
+		},
+		parachain_info: ParachainInfoConfig { parachain_id: id },
+		collator_selection: CollatorSelectionConfig {
+			invulnerables: invulnerables.iter().cloned().map(|(acc, _)| acc).collect::<Vec<_>>(),
+			candidacy_bond: EXISTENTIAL_DEPOSIT * 16,
+		},
+		session: SessionConfig {
+			keys: invulnerables
+				.into_iter()
+				.map(|(acc, aura)| {
+					(
+						acc.clone(),          // account id
+						acc,                  // validator id
+						SessionKeys { aura }, // session keys
+					)
+				})
+				.collect::<Vec<_>>(),
+		},
+		polkadot_xcm: PolkadotXcmConfig { safe_xcm_version: Some(SAFE_XCM_VERSION) },
+		// `WND` is registered from the start, owned by `root` so it can be minted in tests.
+		foreign_assets: ForeignAssetsConfig {
+			assets: vec![(Location::parent(), root.clone(), false, 1)],
+			metadata: vec![(Location::parent(), b"Westend".to_vec(), b"WND".to_vec(), WND_DECIMALS)],
+		},
+		sudo: SudoConfig { key: Some(root) },
+	})
+}
+
+/// A single collator, Alice, like a development node.
+fn development_genesis() -> Value {
+	genesis(
+		vec![(Sr25519Keyring::Alice.to_account_id(), Sr25519Keyring::Alice.public().into())],
+		Sr25519Keyring::well_known().map(|k| k.to_account_id()).collect(),
+		Sr25519Keyring::Alice.to_account_id(),
+		PARACHAIN_ID.into(),
+	)
+}
+
+/// Alice and Bob as collators, same as the other emulated chains.
+fn emulator_genesis() -> Value {
+	genesis(
+		vec![
+			(Sr25519Keyring::Alice.to_account_id(), Sr25519Keyring::Alice.public().into()),
+			(Sr25519Keyring::Bob.to_account_id(), Sr25519Keyring::Bob.public().into()),
+		],
+		Sr25519Keyring::well_known().map(|k| k.to_account_id()).collect(),
+		Sr25519Keyring::Alice.to_account_id(),
+		PARACHAIN_ID.into(),
+	)
+}
+
+/// Provides the JSON representation of predefined genesis config for given `id`.
+pub fn get_preset(id: &PresetId) -> Option<Vec<u8>> {
+	let patch = match id.as_ref() {
+		DEV_RUNTIME_PRESET => development_genesis(),
+		EMULATOR_RUNTIME_PRESET => emulator_genesis(),
+		_ => return None,
+	};
+	Some(
+		serde_json::to_string(&patch)
+			.expect("serialization to json is expected to work. qed.")
+			.into_bytes(),
+	)
+}
+
+/// List of supported presets.
+pub fn preset_names() -> Vec<PresetId> {
+	vec![PresetId::from(DEV_RUNTIME_PRESET), PresetId::from(EMULATOR_RUNTIME_PRESET)]
+}
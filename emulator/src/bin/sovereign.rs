@@ -0,0 +1,67 @@
+//! Prints the account a location gets on our chains.
+//!
+//! The location is the hex of a `VersionedLocation` or its JSON form, as `xcm-codec` reads them.
+//!
+//! ```sh
+//! cargo run -p emulator --bin sovereign -- 0x05010100411f
+//! cargo run -p emulator --bin sovereign -- \
+//!     '{ "V5": { "parents": 1, "interior": { "X1": [{ "Parachain": 2000 }] } } }' asset-hub
+//! ```
+
+use std::{env, process::ExitCode};
+
+use emulator::prelude::{Converter, WESTEND_SS58_PREFIX};
+use xcm::prelude::*;
+use xcm_codec::{parse_hex, Decoded, Kind};
+
+const USAGE: &str =
+	"usage: sovereign <location> [custom-para|asset-hub|relay] [--prefix <ss58 prefix>]";
+
+fn main() -> ExitCode {
+	match run(env::args().skip(1).collect()) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(error) => {
+			eprintln!("{error}\n{USAGE}");
+			ExitCode::FAILURE
+		},
+	}
+}
+
+fn run(mut args: Vec<String>) -> Result<(), String> {
+	let mut prefix = WESTEND_SS58_PREFIX;
+	if let Some(at) = args.iter().position(|arg| arg == "--prefix") {
+		let value = args.get(at + 1).ok_or("missing the prefix")?;
+		prefix = value.parse().map_err(|_| format!("`{value}` is not a prefix"))?;
+		args.drain(at..at + 2);
+	}
+	let (location, converters) = match &args[..] {
+		[location] => (location, Converter::ALL.to_vec()),
+		[location, converter] => (location, vec![converter.parse()?]),
+		_ => return Err("expected a location and maybe a converter".into()),
+	};
+	let location = read_location(location)?;
+
+	for converter in converters {
+		match converter.convert(&location) {
+			Some(account) => println!("{converter}: {} {}", account.hex(), account.ss58(prefix)),
+			None => println!("{converter}: no account"),
+		}
+	}
+	Ok(())
+}
+
+fn read_location(input: &str) -> Result<Location, String> {
+	let decoded = match input.trim_start().starts_with('{') {
+		true => {
+			let json = serde_json::from_str(input).map_err(|error| error.to_string())?;
+			Decoded::from_json(Kind::Location, &json)
+		},
+		false => parse_hex(input).and_then(|bytes| Decoded::decode(Kind::Location, &bytes)),
+	}
+	.map_err(|error| error.to_string())?;
+	match decoded {
+		Decoded::Location(location) =>
+			location.try_into().map_err(|_| "the location can't be read as v5".to_string()),
+		_ => unreachable!("we decoded a location"),
+	}
+}
@@ -0,0 +1,30 @@
+mod genesis;
+
+use emulated_integration_tests_common::impl_accounts_helpers_for_parachain;
+use frame_support::traits::OnInitialize;
+use xcm_emulator::{decl_test_parachains, Parachain};
+
+// BridgeHubRococo Parachain declaration
+decl_test_parachains! {
+	pub struct BridgeHubRococo {
+		genesis = genesis::genesis(),
+		on_init = {
+			bridge_hub_rococo_runtime::AuraExt::on_initialize(1);
+		},
+		runtime = bridge_hub_rococo_runtime,
+		core = {
+			XcmpMessageHandler: bridge_hub_rococo_runtime::XcmpQueue,
+			LocationToAccountId: bridge_hub_rococo_runtime::xcm_config::LocationToAccountId,
+			ParachainInfo: bridge_hub_rococo_runtime::ParachainInfo,
+			MessageOrigin: cumulus_primitives_core::AggregateMessageOrigin,
+		},
+		pallets = {
+			System: bridge_hub_rococo_runtime::System,
+			PolkadotXcm: bridge_hub_rococo_runtime::PolkadotXcm,
+			Balances: bridge_hub_rococo_runtime::Balances,
+			MessageQueue: bridge_hub_rococo_runtime::MessageQueue,
+		}
+	},
+}
+
+impl_accounts_helpers_for_parachain!(BridgeHubRococo);
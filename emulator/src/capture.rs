@@ -0,0 +1,437 @@
+//! Captures the messages chains send to each other, so tests can inspect them.
+//!
+//! The emulator delivers messages as soon as a block is finalized, so we can't catch them in
+//! transit. Instead, right before a block ends, we collect what the chain is about to send,
+//! the same way the collator would, and roll back so nothing changes.
+//!
+//! Use `execute_and_capture` instead of `execute_with` for the blocks you want to capture.
+//! `drain_queues` always captures.
+//!
+//! Messages are decoded into `VersionedXcm`s, so they can be queried, printed or exported
+//! as JSON when a test fails.
+//!
+//! Messages relayed between the bridge hubs don't go through the relay chains, so they're
+//! not captured. What the bridge hubs send to their siblings is.
+
+use crate::{
+	network::{
+		AssetHubRococoPara, AssetHubWestendPara, BridgeHubRococoPara, BridgeHubWestendPara,
+		CustomPara, DexPara, EvmPara, RococoNetwork, RococoRelay, SiblingPara, WestendNetwork,
+		WestendRelay,
+	},
+	prelude::*,
+	timeline::{observe, Pricing},
+};
+
+use codec::{DecodeLimit, Encode};
+use core::{cell::RefCell, fmt, ops::Index};
+use cumulus_primitives_core::{ParaId, XcmpMessageFormat};
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::{Get, Hooks},
+};
+use frame_system::pallet_prelude::{BlockNumberFor, HeaderFor};
+use polkadot_primitives::{
+	runtime_api::runtime_decl_for_parachain_host::ParachainHostV13, InboundDownwardMessage,
+};
+use serde_json::{json, Value};
+use sp_core::hexdisplay::HexDisplay;
+use sp_runtime::{traits::Header, DispatchError, SaturatedConversion};
+use xcm::{
+	opaque::latest::{ROCOCO_GENESIS_HASH, WESTEND_GENESIS_HASH},
+	prelude::*,
+	MAX_XCM_DECODE_DEPTH,
+};
+use xcm_emulator::Network;
+use xcm_runtime_apis::fees::runtime_decl_for_xcm_payment_api::XcmPaymentApiV1;
+
+thread_local! {
+	static CAPTURED: RefCell<Vec<CapturedMessage>> = RefCell::new(Vec::new());
+}
+
+/// How a message travelled between chains.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transport {
+	/// From a parachain to the relay chain.
+	Ump,
+	/// From the relay chain to a parachain.
+	Dmp,
+	/// Between sibling parachains.
+	Hrmp,
+}
+
+/// A message sent from one chain to another.
+///
+/// Chains are identified by their location from the relay chain's point of view:
+/// `Here` for the relay chain itself and `Parachain(id)` for parachains.
+/// Chains of the bridged `RococoNetwork` are prefixed with `GlobalConsensus(Rococo)`, so they
+/// don't clash with the ones of `WestendNetwork`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapturedMessage {
+	pub source: Location,
+	pub destination: Location,
+	/// Block number of the source chain when the message was sent.
+	pub block: u32,
+	pub transport: Transport,
+	/// The id set by the last `SetTopic` instruction, if any.
+	pub topic: Option<XcmHash>,
+	pub message: VersionedXcm<()>,
+}
+
+impl CapturedMessage {
+	fn new(
+		source: Location,
+		destination: Location,
+		block: u32,
+		transport: Transport,
+		message: VersionedXcm<()>,
+	) -> Self {
+		let topic =
+			Xcm::<()>::try_from(message.clone())
+				.ok()
+				.and_then(|xcm| match xcm.inner().last() {
+					Some(SetTopic(id)) => Some(*id),
+					_ => None,
+				});
+		Self { source, destination, block, transport, topic, message }
+	}
+
+	/// The instructions of the message, in the latest XCM version.
+	/// Empty if the message can't be converted.
+	pub fn instructions(&self) -> Vec<Instruction<()>> {
+		Xcm::<()>::try_from(self.message.clone()).map(|xcm| xcm.0).unwrap_or_default()
+	}
+
+	/// Whether any instruction of the message matches `predicate`.
+	pub fn contains(&self, predicate: impl Fn(&Instruction<()>) -> bool) -> bool {
+		self.instructions().iter().any(predicate)
+	}
+
+	/// The message as JSON.
+	/// Instructions are in their debug representation, `encoded` is the SCALE encoded
+	/// `VersionedXcm`.
+	pub fn to_json(&self) -> Value {
+		json!({
+			"source": chain_name(&self.source),
+			"destination": chain_name(&self.destination),
+			"block": self.block,
+			"transport": format!("{:?}", self.transport),
+			"topic": self.topic.map(|topic| format!("0x{}", HexDisplay::from(&topic))),
+			"instructions": self
+				.instructions()
+				.iter()
+				.map(|instruction| format!("{instruction:?}"))
+				.collect::<Vec<_>>(),
+			"encoded": format!("0x{}", HexDisplay::from(&self.message.encode())),
+		})
+	}
+}
+
+impl fmt::Display for CapturedMessage {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{} -> {} via {:?} at block {}",
+			chain_name(&self.source),
+			chain_name(&self.destination),
+			self.transport,
+			self.block,
+		)?;
+		if let Some(topic) = self.topic {
+			write!(f, ", topic 0x{}", HexDisplay::from(&topic))?;
+		}
+		for instruction in self.instructions() {
+			write!(f, "\n    {instruction:?}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Messages captured so far on this thread, in the order they were sent.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MessageLog(Vec<CapturedMessage>);
+
+impl MessageLog {
+	/// Every message captured so far.
+	pub fn get() -> Self {
+		Self(CAPTURED.with(|captured| captured.borrow().clone()))
+	}
+
+	/// Forgets every message captured so far.
+	pub fn clear() {
+		CAPTURED.with(|captured| captured.borrow_mut().clear());
+	}
+
+	/// Messages sent by `source`.
+	pub fn from(self, source: impl Into<Location>) -> Self {
+		let source = source.into();
+		self.filter(|message| message.source == source)
+	}
+
+	/// Messages sent to `destination`.
+	pub fn to(self, destination: impl Into<Location>) -> Self {
+		let destination = destination.into();
+		self.filter(|message| message.destination == destination)
+	}
+
+	/// Messages with the topic `topic`.
+	pub fn with_topic(self, topic: XcmHash) -> Self {
+		self.filter(|message| message.topic == Some(topic))
+	}
+
+	/// Messages with any instruction that matches `predicate`.
+	pub fn containing(self, predicate: impl Fn(&Instruction<()>) -> bool) -> Self {
+		self.filter(|message| message.contains(&predicate))
+	}
+
+	/// Messages that match `predicate`.
+	pub fn filter(self, predicate: impl Fn(&CapturedMessage) -> bool) -> Self {
+		Self(self.0.into_iter().filter(|message| predicate(message)).collect())
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &CapturedMessage> {
+		self.0.iter()
+	}
+
+	/// Every message, one after the other, in a human readable way.
+	pub fn pretty(&self) -> String {
+		self.0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+	}
+
+	/// Every message as a JSON array.
+	pub fn to_json(&self) -> Value {
+		Value::Array(self.0.iter().map(CapturedMessage::to_json).collect())
+	}
+}
+
+impl Index<usize> for MessageLog {
+	type Output = CapturedMessage;
+
+	fn index(&self, index: usize) -> &Self::Output {
+		&self.0[index]
+	}
+}
+
+impl IntoIterator for MessageLog {
+	type Item = CapturedMessage;
+	type IntoIter = std::vec::IntoIter<CapturedMessage>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
+
+/// Executes a block, like `execute_with`, recording every message the chain sends in it.
+/// What the chain did with messages is also observed for `Timeline`s.
+pub trait CaptureMessages {
+	fn execute_and_capture<R>(execute: impl FnOnce() -> R) -> R;
+}
+
+/// The `XcmPaymentApi` of `$runtime`.
+macro_rules! pricing {
+	( $runtime:ty ) => {
+		Pricing {
+			weight_to_fee: |weight, id| <$runtime>::query_weight_to_asset_fee(weight, id).ok(),
+			delivery_fees: |to, xcm| <$runtime>::query_delivery_fees(to, xcm).ok(),
+		}
+	};
+}
+
+macro_rules! impl_capture_messages_for_parachain {
+	( $chain:ty, $network:expr ) => {
+		impl CaptureMessages for $chain {
+			fn execute_and_capture<R>(execute: impl FnOnce() -> R) -> R {
+				let network: Location = $network;
+				let chain = within(&network, Parachain(<$chain>::para_id().into()));
+				<$chain>::execute_with(|| {
+					let result = execute();
+					record(parachain_outbound::<<$chain as Chain>::Runtime>(network));
+					observe::<<$chain as Chain>::Runtime>(
+						chain,
+						pricing!(<$chain as Chain>::Runtime),
+					);
+					result
+				})
+			}
+		}
+	};
+}
+
+macro_rules! impl_capture_messages_for_relay {
+	( $chain:ty, $network:ty, $prefix:expr ) => {
+		impl CaptureMessages for $chain {
+			fn execute_and_capture<R>(execute: impl FnOnce() -> R) -> R {
+				let network: Location = $prefix;
+				<$chain>::execute_with(|| {
+					type Runtime = <$chain as Chain>::Runtime;
+					let result = execute();
+					record(relay_outbound::<Runtime>(
+						network.clone(),
+						<$network as Network>::para_ids(),
+						Runtime::dmq_contents,
+					));
+					observe::<Runtime>(network, pricing!(Runtime));
+					result
+				})
+			}
+		}
+	};
+}
+
+impl_capture_messages_for_parachain!(AssetHubWestendPara, Here.into());
+impl_capture_messages_for_parachain!(BridgeHubWestendPara, Here.into());
+impl_capture_messages_for_parachain!(CustomPara, Here.into());
+impl_capture_messages_for_parachain!(SiblingPara, Here.into());
+impl_capture_messages_for_parachain!(EvmPara, Here.into());
+impl_capture_messages_for_parachain!(DexPara, Here.into());
+impl_capture_messages_for_parachain!(AssetHubRococoPara, rococo());
+impl_capture_messages_for_parachain!(BridgeHubRococoPara, rococo());
+
+impl_capture_messages_for_relay!(WestendRelay, WestendNetwork, Here.into());
+impl_capture_messages_for_relay!(RococoRelay, RococoNetwork, rococo());
+
+/// The prefix of every chain of `RococoNetwork`.
+pub(crate) fn rococo() -> Location {
+	GlobalConsensus(ByGenesis(ROCOCO_GENESIS_HASH)).into()
+}
+
+/// A chain of the network with prefix `network`.
+pub(crate) fn within(network: &Location, chain: Junction) -> Location {
+	network.clone().pushed_with_interior(chain).expect("network prefixes are short")
+}
+
+fn record(messages: Vec<CapturedMessage>) {
+	CAPTURED.with(|captured| captured.borrow_mut().extend(messages));
+}
+
+/// Upward and horizontal messages a parachain sends in the current block.
+///
+/// They're only handed over in `on_finalize`, so we run it early and roll it back.
+fn parachain_outbound<T: cumulus_pallet_parachain_system::Config>(
+	network: Location,
+) -> Vec<CapturedMessage> {
+	type ParachainSystem<T> = cumulus_pallet_parachain_system::Pallet<T>;
+	let number = frame_system::Pallet::<T>::block_number();
+	let collation_info = with_transaction(|| {
+		<ParachainSystem<T> as Hooks<BlockNumberFor<T>>>::on_finalize(number);
+		let header = HeaderFor::<T>::new(
+			number,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		let collation_info = ParachainSystem::<T>::collect_collation_info(&header);
+		TransactionOutcome::Rollback(Ok::<_, DispatchError>(collation_info))
+	})
+	.expect("collecting the messages doesn't fail");
+
+	let source = within(&network, Parachain(T::SelfParaId::get().into()));
+	let block = number.saturated_into();
+	let mut messages = Vec::new();
+	for message in collation_info.upward_messages {
+		if let Some(message) = decode(&mut &message[..]) {
+			messages.push(CapturedMessage::new(
+				source.clone(),
+				network.clone(),
+				block,
+				Transport::Ump,
+				message,
+			));
+		}
+	}
+	for outbound in collation_info.horizontal_messages {
+		let destination = within(&network, Parachain(outbound.recipient.into()));
+		for message in decode_xcmp(&outbound.data) {
+			messages.push(CapturedMessage::new(
+				source.clone(),
+				destination.clone(),
+				block,
+				Transport::Hrmp,
+				message,
+			));
+		}
+	}
+	messages
+}
+
+/// Downward messages the relay chain sends to `para_ids` in the current block.
+/// `dmq_contents` is the runtime API of the relay chain that returns a parachain's queue.
+fn relay_outbound<T: frame_system::Config>(
+	network: Location,
+	para_ids: Vec<u32>,
+	dmq_contents: impl Fn(ParaId) -> Vec<InboundDownwardMessage<BlockNumberFor<T>>>,
+) -> Vec<CapturedMessage> {
+	let number = frame_system::Pallet::<T>::block_number();
+	let mut messages = Vec::new();
+	for para_id in para_ids {
+		for inbound in dmq_contents(ParaId::from(para_id)) {
+			// Older messages are still in the queue, they were captured back then.
+			if inbound.sent_at != number {
+				continue;
+			}
+			if let Some(message) = decode(&mut &inbound.msg[..]) {
+				messages.push(CapturedMessage::new(
+					network.clone(),
+					within(&network, Parachain(para_id)),
+					number.saturated_into(),
+					Transport::Dmp,
+					message,
+				));
+			}
+		}
+	}
+	messages
+}
+
+fn decode(data: &mut &[u8]) -> Option<VersionedXcm<()>> {
+	VersionedXcm::<()>::decode_with_depth_limit(MAX_XCM_DECODE_DEPTH, data).ok()
+}
+
+/// Splits an XCMP page into the messages it carries.
+/// Signals and blobs aren't XCMs, so they're skipped.
+fn decode_xcmp(mut data: &[u8]) -> Vec<VersionedXcm<()>> {
+	let mut messages = Vec::new();
+	if !matches!(
+		XcmpMessageFormat::decode_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut data),
+		Ok(XcmpMessageFormat::ConcatenatedVersionedXcm)
+	) {
+		return messages;
+	}
+	while !data.is_empty() {
+		match decode(&mut data) {
+			Some(message) => messages.push(message),
+			None => break,
+		}
+	}
+	messages
+}
+
+fn chain_name(location: &Location) -> String {
+	match location.unpack() {
+		(0, []) => "relay".into(),
+		(0, [Parachain(id)]) => format!("para {id}"),
+		(0, [GlobalConsensus(network)]) => format!("{} relay", network_name(network)),
+		(0, [GlobalConsensus(network), Parachain(id)]) =>
+			format!("{} para {id}", network_name(network)),
+		_ => format!("{location:?}"),
+	}
+}
+
+fn network_name(network: &NetworkId) -> String {
+	match network {
+		ByGenesis(hash) if *hash == ROCOCO_GENESIS_HASH => "rococo".into(),
+		ByGenesis(hash) if *hash == WESTEND_GENESIS_HASH => "westend".into(),
+		Polkadot => "polkadot".into(),
+		Kusama => "kusama".into(),
+		_ => format!("{network:?}"),
+	}
+}
@@ -0,0 +1,21 @@
+use dex_parachain_runtime::genesis_config_presets::{get_preset, EMULATOR_RUNTIME_PRESET};
+use emulated_integration_tests_common::build_genesis_storage;
+use sc_chain_spec::json_merge;
+use sp_genesis_builder::PresetId;
+use sp_runtime::Storage;
+
+/// Genesis of the DEX parachain, as given by its emulator preset.
+pub fn genesis() -> Storage {
+	let patch = get_preset(&PresetId::from(EMULATOR_RUNTIME_PRESET))
+		.expect("the emulator preset is defined by the runtime");
+	let mut config = serde_json::to_value(dex_parachain_runtime::RuntimeGenesisConfig::default())
+		.expect("the default genesis config can be serialized");
+	json_merge(&mut config, serde_json::from_slice(&patch).expect("presets are valid JSON"));
+	let genesis_config: dex_parachain_runtime::RuntimeGenesisConfig =
+		serde_json::from_value(config).expect("the patched genesis config is valid");
+
+	build_genesis_storage(
+		&genesis_config,
+		dex_parachain_runtime::WASM_BINARY.expect("WASM binary was not built, please build it!"),
+	)
+}
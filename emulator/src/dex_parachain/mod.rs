@@ -0,0 +1,36 @@
+mod genesis;
+
+use emulated_integration_tests_common::{
+	impl_accounts_helpers_for_parachain, impl_foreign_assets_helpers_for_parachain,
+};
+use frame_support::traits::OnInitialize;
+use xcm_emulator::decl_test_parachains;
+
+decl_test_parachains! {
+	pub struct Dex {
+		genesis = genesis::genesis(),
+		on_init = {
+			dex_parachain_runtime::AuraExt::on_initialize(1);
+		},
+		runtime = dex_parachain_runtime,
+		core = {
+			XcmpMessageHandler: dex_parachain_runtime::XcmpQueue,
+			LocationToAccountId: dex_parachain_runtime::configs::xcm_config::LocationToAccountId,
+			ParachainInfo: dex_parachain_runtime::ParachainInfo,
+			MessageOrigin: cumulus_primitives_core::AggregateMessageOrigin,
+		},
+		pallets = {
+			System: dex_parachain_runtime::System,
+			Balances: dex_parachain_runtime::Balances,
+			ForeignAssets: dex_parachain_runtime::ForeignAssets,
+			PoolAssets: dex_parachain_runtime::PoolAssets,
+			AssetConversion: dex_parachain_runtime::AssetConversion,
+			PolkadotXcm: dex_parachain_runtime::PolkadotXcm,
+			MessageQueue: dex_parachain_runtime::MessageQueue,
+			XcmpQueue: dex_parachain_runtime::XcmpQueue,
+		}
+	},
+}
+
+impl_foreign_assets_helpers_for_parachain!(Dex, xcm::v5::Location);
+impl_accounts_helpers_for_parachain!(Dex);
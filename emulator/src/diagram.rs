@@ -0,0 +1,166 @@
+//! Turns captured messages into diagrams.
+//!
+//! Chains are participants and every message is an arrow, labelled with its instructions
+//! and the assets it carries. Messages that failed on their destination are highlighted.
+//! Paste the Mermaid output into any markdown that renders it, or feed the Graphviz output
+//! to `dot`.
+
+use crate::{
+	capture::{CapturedMessage, MessageLog},
+	timeline::Timeline,
+};
+
+use std::collections::BTreeSet;
+use xcm::prelude::*;
+
+impl MessageLog {
+	/// A Mermaid sequence diagram of every message.
+	pub fn to_mermaid(&self) -> String {
+		let mut lines = vec!["sequenceDiagram".to_string()];
+		for chain in participants(self) {
+			lines.push(format!("    participant {} as {}", node_id(&chain), chain_label(&chain)));
+		}
+		let failures = failures(self);
+		for (index, message) in self.iter().enumerate() {
+			let failed = failed(message, &failures);
+			let arrow = if failed { "-x" } else { "->>" };
+			let label = label(index, message).join("<br/>").replace(';', ",");
+			lines.push(format!(
+				"    {}{arrow}{}: {label}",
+				node_id(&message.source),
+				node_id(&message.destination),
+			));
+			if failed {
+				lines.push(format!("    Note over {}: FAILED", node_id(&message.destination)));
+			}
+		}
+		lines.join("\n")
+	}
+
+	/// A Graphviz digraph of every message.
+	pub fn to_graphviz(&self) -> String {
+		let mut lines = vec![
+			"digraph xcm {".to_string(),
+			"    rankdir=LR;".to_string(),
+			"    node [shape=box];".to_string(),
+		];
+		for chain in participants(self) {
+			lines.push(format!("    {} [label=\"{}\"];", node_id(&chain), chain_label(&chain)));
+		}
+		let failures = failures(self);
+		for (index, message) in self.iter().enumerate() {
+			let label = label(index, message).join("\\n").replace('"', "'");
+			let style = if failed(message, &failures) { ", color=red, fontcolor=red" } else { "" };
+			lines.push(format!(
+				"    {} -> {} [label=\"{label}\"{style}];",
+				node_id(&message.source),
+				node_id(&message.destination),
+			));
+		}
+		lines.push("}".to_string());
+		lines.join("\n")
+	}
+}
+
+/// Every chain involved, in order of appearance.
+fn participants(log: &MessageLog) -> Vec<Location> {
+	let mut chains: Vec<Location> = Vec::new();
+	for message in log.iter() {
+		for chain in [&message.source, &message.destination] {
+			if !chains.contains(chain) {
+				chains.push(chain.clone());
+			}
+		}
+	}
+	chains
+}
+
+/// The chains that failed to process a message, with its topic.
+/// Only known if the destination's blocks were observed.
+fn failures(log: &MessageLog) -> Vec<(XcmHash, Location)> {
+	let topics: BTreeSet<XcmHash> = log.iter().filter_map(|message| message.topic).collect();
+	topics
+		.into_iter()
+		.flat_map(|topic| {
+			Timeline::follow(topic)
+				.hops
+				.into_iter()
+				.filter(|hop| hop.processed.as_ref().is_some_and(|processed| !processed.success))
+				.map(move |hop| (topic, hop.chain))
+		})
+		.collect()
+}
+
+/// Whether the destination failed to process the message.
+fn failed(message: &CapturedMessage, failures: &[(XcmHash, Location)]) -> bool {
+	message
+		.topic
+		.is_some_and(|topic| failures.contains(&(topic, message.destination.clone())))
+}
+
+/// The lines describing a message: its order, its top level instructions and its assets.
+fn label(index: usize, message: &CapturedMessage) -> Vec<String> {
+	let instructions = message.instructions();
+	let names: Vec<_> = instructions
+		.iter()
+		.filter(|instruction| !matches!(instruction, SetTopic(_)))
+		.map(instruction_name)
+		.collect();
+	let mut lines = vec![format!("{}. {:?}: {}", index + 1, message.transport, names.join(", "))];
+	for instruction in &instructions {
+		let assets = match instruction {
+			WithdrawAsset(assets) |
+			ReserveAssetDeposited(assets) |
+			ReceiveTeleportedAsset(assets) => assets,
+			_ => continue,
+		};
+		lines.extend(assets.inner().iter().map(asset_label));
+	}
+	lines
+}
+
+/// The name of the instruction, without its parameters.
+fn instruction_name(instruction: &Instruction<()>) -> String {
+	let debug = format!("{instruction:?}");
+	debug.split(['(', ' ', '{']).next().unwrap_or_default().to_string()
+}
+
+fn asset_label(asset: &Asset) -> String {
+	match &asset.fun {
+		Fungible(amount) => format!("{amount} of {}", location_label(&asset.id.0)),
+		NonFungible(instance) => format!("{instance:?} of {}", location_label(&asset.id.0)),
+	}
+}
+
+/// A compact location, like `../Parachain(2000)`.
+fn location_label(location: &Location) -> String {
+	let parts: Vec<String> = (0..location.parent_count())
+		.map(|_| "..".to_string())
+		.chain(location.interior().iter().map(|junction| format!("{junction:?}")))
+		.collect();
+	if parts.is_empty() {
+		"Here".to_string()
+	} else {
+		parts.join("/")
+	}
+}
+
+/// An identifier both Mermaid and Graphviz accept.
+fn node_id(chain: &Location) -> String {
+	match chain.unpack() {
+		(0, []) => "relay".to_string(),
+		(0, [Parachain(id)]) => format!("para_{id}"),
+		_ => {
+			let label = location_label(chain).replace(|c: char| !c.is_alphanumeric(), "_");
+			format!("chain_{label}")
+		},
+	}
+}
+
+fn chain_label(chain: &Location) -> String {
+	match chain.unpack() {
+		(0, []) => "Relay chain".to_string(),
+		(0, [Parachain(id)]) => format!("Parachain {id}"),
+		_ => location_label(chain),
+	}
+}
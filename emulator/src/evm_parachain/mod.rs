@@ -0,0 +1,30 @@
+mod genesis;
+
+use frame_support::traits::OnInitialize;
+use xcm_emulator::decl_test_parachains;
+
+// The helpers of `emulated_integration_tests_common` assume 32 byte accounts, so this chain
+// only gets the declaration.
+decl_test_parachains! {
+	pub struct Evm {
+		genesis = genesis::genesis(),
+		on_init = {
+			evm_parachain_runtime::AuraExt::on_initialize(1);
+		},
+		runtime = evm_parachain_runtime,
+		core = {
+			XcmpMessageHandler: evm_parachain_runtime::XcmpQueue,
+			LocationToAccountId: evm_parachain_runtime::configs::xcm_config::LocationToAccountId,
+			ParachainInfo: evm_parachain_runtime::ParachainInfo,
+			MessageOrigin: cumulus_primitives_core::AggregateMessageOrigin,
+		},
+		pallets = {
+			System: evm_parachain_runtime::System,
+			Balances: evm_parachain_runtime::Balances,
+			ForeignAssets: evm_parachain_runtime::ForeignAssets,
+			PolkadotXcm: evm_parachain_runtime::PolkadotXcm,
+			MessageQueue: evm_parachain_runtime::MessageQueue,
+			XcmpQueue: evm_parachain_runtime::XcmpQueue,
+		}
+	},
+}
@@ -0,0 +1,365 @@
+//! Declares the state the network starts from.
+//!
+//! The genesis of each chain is fixed, but tests usually need some more: foreign assets
+//! registered, accounts funded, pools with liquidity. Instead of reaching that state with
+//! calls one after the other, tests can declare it and apply it before the first block.
+//!
+//! ```ignore
+//! NetworkGenesis::new()
+//! 	.asset_hub(ChainGenesis::new().foreign_asset(para_token.clone(), owner.clone(), 1))
+//! 	.custom_para(ChainGenesis::new().balance(sender, 10 * PARA_UNITS))
+//! 	.apply();
+//! ```
+
+use crate::{
+	asset_hub_rococo::AssetHubRococoParaPallet,
+	asset_hub_westend::AssetHubWestendParaPallet,
+	bridge_hub_rococo::BridgeHubRococoParaPallet,
+	bridge_hub_westend::BridgeHubWestendParaPallet,
+	dex_parachain::DexParaPallet,
+	network::{
+		AssetHubRococoPara, AssetHubWestendPara, BridgeHubRococoPara, BridgeHubWestendPara,
+		CustomPara, DexPara, RococoRelay, SiblingPara, WestendRelay,
+	},
+	parachain::{CustomParaPallet, SiblingParaPallet},
+	prelude::*,
+	rococo::RococoRelayPallet,
+	westend::WestendRelayPallet,
+};
+
+use frame_support::{
+	assert_ok,
+	traits::{
+		fungible,
+		fungibles::{self, Create},
+	},
+};
+use xcm::prelude::*;
+use xcm_emulator::RelayChain;
+
+/// A foreign asset to register.
+#[derive(Clone, Debug)]
+pub struct ForeignAsset {
+	pub id: Location,
+	pub owner: AccountId,
+	pub is_sufficient: bool,
+	pub min_balance: u128,
+}
+
+/// A pool to create in the asset hub, with its initial liquidity.
+#[derive(Clone, Debug)]
+pub struct Pool {
+	pub asset1: Location,
+	pub asset2: Location,
+	pub amount1: u128,
+	pub amount2: u128,
+	/// Creates the pool and provides the liquidity, so needs to hold both assets.
+	pub provider: AccountId,
+}
+
+/// How a declared amount changes a balance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Update {
+	/// The amount is added to what the account already has, like `fund_accounts` does.
+	Add,
+	/// The balance becomes exactly the amount.
+	Set,
+}
+
+/// The state of a single chain on top of its genesis.
+///
+/// Balances are added to what the genesis gives, unless they're declared with `set_balance`
+/// or `set_foreign_balance`.
+#[derive(Clone, Debug, Default)]
+pub struct ChainGenesis {
+	foreign_assets: Vec<ForeignAsset>,
+	balances: Vec<(AccountId, u128, Update)>,
+	sovereign_balances: Vec<(Location, u128)>,
+	foreign_balances: Vec<(Location, AccountId, u128, Update)>,
+	pools: Vec<Pool>,
+}
+
+impl ChainGenesis {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers the foreign asset `id`, not sufficient.
+	pub fn foreign_asset(
+		self,
+		id: impl Into<Location>,
+		owner: AccountId,
+		min_balance: u128,
+	) -> Self {
+		self.foreign_asset_with(ForeignAsset {
+			id: id.into(),
+			owner,
+			is_sufficient: false,
+			min_balance,
+		})
+	}
+
+	/// Registers a foreign asset with every parameter.
+	pub fn foreign_asset_with(mut self, asset: ForeignAsset) -> Self {
+		self.foreign_assets.push(asset);
+		self
+	}
+
+	/// Adds `amount` to the native balance of `who`.
+	pub fn balance(mut self, who: AccountId, amount: u128) -> Self {
+		self.balances.push((who, amount, Update::Add));
+		self
+	}
+
+	/// Sets the native balance of `who` to exactly `amount`.
+	pub fn set_balance(mut self, who: AccountId, amount: u128) -> Self {
+		self.balances.push((who, amount, Update::Set));
+		self
+	}
+
+	/// Adds `amount` to the native balance of the sovereign account of `location` on this
+	/// chain.
+	pub fn sovereign_balance(mut self, location: impl Into<Location>, amount: u128) -> Self {
+		self.sovereign_balances.push((location.into(), amount));
+		self
+	}
+
+	/// Adds `amount` to the balance of `who` in the foreign asset `id`.
+	/// The asset needs to be registered, either in genesis or with `foreign_asset`.
+	pub fn foreign_balance(
+		mut self,
+		id: impl Into<Location>,
+		who: AccountId,
+		amount: u128,
+	) -> Self {
+		self.foreign_balances.push((id.into(), who, amount, Update::Add));
+		self
+	}
+
+	/// Sets the balance of `who` in the foreign asset `id` to exactly `amount`.
+	pub fn set_foreign_balance(
+		mut self,
+		id: impl Into<Location>,
+		who: AccountId,
+		amount: u128,
+	) -> Self {
+		self.foreign_balances.push((id.into(), who, amount, Update::Set));
+		self
+	}
+
+	/// Creates a pool between `asset1` and `asset2` and adds liquidity to it.
+	/// Only the asset hubs and the DEX have pools.
+	pub fn pool(
+		mut self,
+		(asset1, amount1): (impl Into<Location>, u128),
+		(asset2, amount2): (impl Into<Location>, u128),
+		provider: AccountId,
+	) -> Self {
+		self.pools.push(Pool {
+			asset1: asset1.into(),
+			asset2: asset2.into(),
+			amount1,
+			amount2,
+			provider,
+		});
+		self
+	}
+}
+
+/// The native balances of `$genesis`, sovereign accounts included.
+/// Converting a location doesn't need the chain's storage, so it's done outside of it.
+macro_rules! balances {
+	( $chain:ty, $genesis:expr ) => {{
+		let sovereign_balances =
+			$genesis.sovereign_balances.into_iter().map(|(location, amount)| {
+				(<$chain>::sovereign_account_id_of(location), amount, Update::Add)
+			});
+		$genesis.balances.into_iter().chain(sovereign_balances).collect::<Vec<_>>()
+	}};
+}
+
+/// Adds or sets a balance with the `Mutate` trait of `$module`, `fungible` or `fungibles`.
+macro_rules! update_balance {
+	( $pallet:ty, $module:ident, $update:expr, $($args:expr),+ ) => {
+		match $update {
+			Update::Add => {
+				assert_ok!(<$pallet as $module::Mutate<_>>::mint_into($($args),+));
+			},
+			Update::Set => {
+				<$pallet as $module::Mutate<_>>::set_balance($($args),+);
+			},
+		}
+	};
+}
+
+/// Applies native balances to a chain without foreign assets, like the relay chains or the
+/// bridge hubs.
+macro_rules! apply_native {
+	( $chain:ty, $pallet:ident, $genesis:expr ) => {{
+		let genesis: ChainGenesis = $genesis;
+		assert!(
+			genesis.foreign_assets.is_empty() &&
+				genesis.foreign_balances.is_empty() &&
+				genesis.pools.is_empty(),
+			"{} only has native balances",
+			stringify!($chain)
+		);
+		let balances = balances!($chain, genesis);
+		<$chain>::ext_wrapper(|| {
+			type Balances = <$chain as $pallet>::Balances;
+			for (who, amount, update) in balances {
+				update_balance!(Balances, fungible, update, &who, amount);
+			}
+		});
+	}};
+}
+
+/// Applies everything but pools to a parachain.
+macro_rules! apply_to_parachain {
+	( $chain:ty, $pallet:ident, $genesis:expr ) => {{
+		let genesis: ChainGenesis = $genesis;
+		assert!(genesis.pools.is_empty(), "only the asset hubs and the DEX have pools");
+		let balances = balances!($chain, genesis);
+		<$chain>::ext_wrapper(|| {
+			type Balances = <$chain as $pallet>::Balances;
+			type ForeignAssets = <$chain as $pallet>::ForeignAssets;
+			for asset in genesis.foreign_assets {
+				assert_ok!(<ForeignAssets as Create<_>>::create(
+					asset.id,
+					asset.owner,
+					asset.is_sufficient,
+					asset.min_balance,
+				));
+			}
+			for (who, amount, update) in balances {
+				update_balance!(Balances, fungible, update, &who, amount);
+			}
+			for (id, who, amount, update) in genesis.foreign_balances {
+				update_balance!(ForeignAssets, fungibles, update, id, &who, amount);
+			}
+		});
+	}};
+}
+
+/// Creates the pools of an asset hub or the DEX.
+macro_rules! apply_pools {
+	( $chain:ty, $pallet:ident, $pools:expr ) => {{
+		let pools: Vec<Pool> = $pools;
+		<$chain>::ext_wrapper(|| {
+			type AssetConversion = <$chain as $pallet>::AssetConversion;
+			type RuntimeOrigin = <$chain as Chain>::RuntimeOrigin;
+			for pool in pools {
+				assert_ok!(AssetConversion::create_pool(
+					RuntimeOrigin::signed(pool.provider.clone()),
+					Box::new(pool.asset1.clone()),
+					Box::new(pool.asset2.clone()),
+				));
+				assert_ok!(AssetConversion::add_liquidity(
+					RuntimeOrigin::signed(pool.provider.clone()),
+					Box::new(pool.asset1),
+					Box::new(pool.asset2),
+					pool.amount1,
+					pool.amount2,
+					0,
+					0,
+					pool.provider,
+				));
+			}
+		});
+	}};
+}
+
+/// The state of every chain in the network on top of their genesis.
+///
+/// Accounts are 32 bytes, so `EvmPara` isn't covered. Its preset already funds its development
+/// accounts.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkGenesis {
+	westend: ChainGenesis,
+	asset_hub: ChainGenesis,
+	bridge_hub: ChainGenesis,
+	custom_para: ChainGenesis,
+	sibling_para: ChainGenesis,
+	dex_para: ChainGenesis,
+	rococo: ChainGenesis,
+	asset_hub_rococo: ChainGenesis,
+	bridge_hub_rococo: ChainGenesis,
+}
+
+impl NetworkGenesis {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The relay chain only has native balances.
+	pub fn westend(mut self, genesis: ChainGenesis) -> Self {
+		self.westend = genesis;
+		self
+	}
+
+	pub fn asset_hub(mut self, genesis: ChainGenesis) -> Self {
+		self.asset_hub = genesis;
+		self
+	}
+
+	/// The bridge hubs only have native balances.
+	pub fn bridge_hub(mut self, genesis: ChainGenesis) -> Self {
+		self.bridge_hub = genesis;
+		self
+	}
+
+	pub fn custom_para(mut self, genesis: ChainGenesis) -> Self {
+		self.custom_para = genesis;
+		self
+	}
+
+	pub fn sibling_para(mut self, genesis: ChainGenesis) -> Self {
+		self.sibling_para = genesis;
+		self
+	}
+
+	pub fn dex_para(mut self, genesis: ChainGenesis) -> Self {
+		self.dex_para = genesis;
+		self
+	}
+
+	/// The chains of the bridged network, `RococoNetwork`.
+	pub fn rococo(mut self, genesis: ChainGenesis) -> Self {
+		self.rococo = genesis;
+		self
+	}
+
+	pub fn asset_hub_rococo(mut self, genesis: ChainGenesis) -> Self {
+		self.asset_hub_rococo = genesis;
+		self
+	}
+
+	pub fn bridge_hub_rococo(mut self, genesis: ChainGenesis) -> Self {
+		self.bridge_hub_rococo = genesis;
+		self
+	}
+
+	/// Applies the declared state to every chain.
+	///
+	/// Call it before the first block, so everything that follows starts from this state.
+	/// Foreign assets are registered first, then balances are updated and finally pools are
+	/// created.
+	pub fn apply(mut self) {
+		apply_native!(WestendRelay, WestendRelayPallet, self.westend);
+		let pools = core::mem::take(&mut self.asset_hub.pools);
+		apply_to_parachain!(AssetHubWestendPara, AssetHubWestendParaPallet, self.asset_hub);
+		apply_pools!(AssetHubWestendPara, AssetHubWestendParaPallet, pools);
+		apply_native!(BridgeHubWestendPara, BridgeHubWestendParaPallet, self.bridge_hub);
+		apply_to_parachain!(CustomPara, CustomParaPallet, self.custom_para);
+		apply_to_parachain!(SiblingPara, SiblingParaPallet, self.sibling_para);
+		let pools = core::mem::take(&mut self.dex_para.pools);
+		apply_to_parachain!(DexPara, DexParaPallet, self.dex_para);
+		apply_pools!(DexPara, DexParaPallet, pools);
+
+		apply_native!(RococoRelay, RococoRelayPallet, self.rococo);
+		let pools = core::mem::take(&mut self.asset_hub_rococo.pools);
+		apply_to_parachain!(AssetHubRococoPara, AssetHubRococoParaPallet, self.asset_hub_rococo);
+		apply_pools!(AssetHubRococoPara, AssetHubRococoParaPallet, pools);
+		apply_native!(BridgeHubRococoPara, BridgeHubRococoParaPallet, self.bridge_hub_rococo);
+	}
+}
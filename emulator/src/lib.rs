@@ -1,27 +1,74 @@
+mod asset_hub_rococo;
 mod asset_hub_westend;
+mod bridge_hub_rococo;
+mod bridge_hub_westend;
+mod capture;
+mod dex_parachain;
+mod diagram;
+mod evm_parachain;
+mod genesis_builder;
 mod network;
 mod parachain;
+mod queues;
+mod rococo;
+mod route;
+mod sovereign;
+mod timeline;
+mod transfer;
 mod westend;
 
 pub mod prelude {
 	use super::*;
 
 	pub use network::{
+		AssetHubRococoPara as AssetHubRococo,
+		AssetHubRococoParaReceiver as AssetHubRococoReceiver,
+		AssetHubRococoParaSender as AssetHubRococoSender,
 		AssetHubWestendPara as AssetHubWestend,
 		AssetHubWestendParaReceiver as AssetHubWestendReceiver,
-		AssetHubWestendParaSender as AssetHubWestendSender, CustomPara, CustomParaReceiver,
-		CustomParaSender, WestendRelay as Westend, WestendRelayReceiver as WestendReceiver,
+		AssetHubWestendParaSender as AssetHubWestendSender,
+		BridgeHubRococoPara as BridgeHubRococo,
+		BridgeHubRococoParaReceiver as BridgeHubRococoReceiver,
+		BridgeHubRococoParaSender as BridgeHubRococoSender,
+		BridgeHubWestendPara as BridgeHubWestend,
+		BridgeHubWestendParaReceiver as BridgeHubWestendReceiver,
+		BridgeHubWestendParaSender as BridgeHubWestendSender, CustomPara, CustomParaReceiver,
+		CustomParaSender, DexPara, DexParaReceiver, DexParaSender, EvmPara,
+		RococoRelay as Rococo, RococoRelayReceiver as RococoReceiver,
+		RococoRelaySender as RococoSender, SiblingPara, SiblingParaReceiver, SiblingParaSender,
+		WestendRelay as Westend, WestendRelayReceiver as WestendReceiver,
 		WestendRelaySender as WestendSender,
 	};
 
+	pub use asset_hub_rococo::AssetHubRococoParaPallet as AssetHubRococoPallet;
 	pub use asset_hub_westend::AssetHubWestendParaPallet as AssetHubWestendPallet;
-	pub use parachain::CustomParaPallet;
+	pub use bridge_hub_rococo::BridgeHubRococoParaPallet as BridgeHubRococoPallet;
+	pub use bridge_hub_westend::BridgeHubWestendParaPallet as BridgeHubWestendPallet;
+	pub use dex_parachain::DexParaPallet;
+	pub use evm_parachain::EvmParaPallet;
+	pub use parachain::{CustomParaPallet, SiblingParaPallet};
+	pub use rococo::RococoRelayPallet as RococoPallet;
 	pub use westend::WestendRelayPallet as WestendPallet;
 
+	pub use capture::{CaptureMessages, CapturedMessage, MessageLog, Transport};
+	pub use genesis_builder::{ChainGenesis, ForeignAsset, NetworkGenesis, Pool};
+	pub use queues::{drain_queues, has_pending_messages, StillPending};
+	pub use route::{Route, RouteError, RoutePlanner};
+	pub use sovereign::{Converter, SovereignAccount, WESTEND_SS58_PREFIX};
+	pub use timeline::{clear_timelines, Hop, Processed, Timeline};
+	pub use transfer::{TransferBuilder, TransferError, Trust, TrustEntry, TrustTable};
+
 	pub use xcm_emulator::{assert_expected_events, Chain, Parachain, TestExt};
 
 	pub use sp_runtime::AccountId32 as AccountId;
 
+	pub use dex_parachain_runtime::{CENTS as DEX_CENTS, UNITS as DEX_UNITS};
+	pub use evm_parachain_runtime::{
+		account::AccountId20,
+		genesis_config_presets::{ALITH, BALTATHAR},
+		CENTS as EVM_CENTS, UNITS as EVM_UNITS,
+	};
 	pub use parachain_runtime::{CENTS as PARA_CENTS, UNITS as PARA_UNITS};
+	pub use rococo_runtime_constants::currency::{CENTS as ROC_CENTS, UNITS as ROC_UNITS};
 	pub use westend_runtime_constants::currency::{CENTS as WND_CENTS, UNITS as WND_UNITS};
 }
@@ -1,21 +1,81 @@
-use crate::{asset_hub_westend::AssetHubWestend, parachain::Custom, westend::Westend};
+use crate::{
+	asset_hub_rococo::AssetHubRococo,
+	asset_hub_westend::AssetHubWestend,
+	bridge_hub_rococo::BridgeHubRococo,
+	bridge_hub_westend::BridgeHubWestend,
+	dex_parachain::Dex,
+	evm_parachain::Evm,
+	parachain::{Custom, Sibling},
+	rococo::Rococo,
+	westend::Westend,
+};
 
-use emulated_integration_tests_common::accounts::{ALICE, BOB};
-use xcm_emulator::{decl_test_networks, decl_test_sender_receiver_accounts_parameter_types};
+use emulated_integration_tests_common::{
+	accounts::{ALICE, BOB},
+	impls::BridgeHubMessageHandler,
+};
+use xcm_emulator::{
+	decl_test_bridges, decl_test_networks, decl_test_sender_receiver_accounts_parameter_types,
+};
 
 decl_test_networks! {
 	pub struct WestendNetwork {
 		relay_chain = Westend,
 		parachains = vec![
 			AssetHubWestend,
+			BridgeHubWestend,
 			Custom,
+			Sibling,
+			Evm,
+			Dex,
 		],
-		bridge = ()
+		bridge = WestendRococoMockBridge
+	},
+	pub struct RococoNetwork {
+		relay_chain = Rococo,
+		parachains = vec![
+			AssetHubRococo,
+			BridgeHubRococo,
+		],
+		bridge = RococoWestendMockBridge
+	}
+}
+
+// Messages are relayed between the bridge hubs as soon as they're sent, without proofs.
+decl_test_bridges! {
+	pub struct WestendRococoMockBridge {
+		source = BridgeHubWestendPara,
+		target = BridgeHubRococoPara,
+		handler = WestendRococoMessageHandler
+	},
+	pub struct RococoWestendMockBridge {
+		source = BridgeHubRococoPara,
+		target = BridgeHubWestendPara,
+		handler = RococoWestendMessageHandler
 	}
 }
 
+type WestendRococoMessageHandler = BridgeHubMessageHandler<
+	bridge_hub_westend_runtime::Runtime,
+	bridge_hub_westend_runtime::bridge_to_rococo_config::WithBridgeHubRococoMessagesInstance,
+	bridge_hub_rococo_runtime::Runtime,
+	bridge_hub_rococo_runtime::bridge_to_westend_config::WithBridgeHubWestendMessagesInstance,
+>;
+type RococoWestendMessageHandler = BridgeHubMessageHandler<
+	bridge_hub_rococo_runtime::Runtime,
+	bridge_hub_rococo_runtime::bridge_to_westend_config::WithBridgeHubWestendMessagesInstance,
+	bridge_hub_westend_runtime::Runtime,
+	bridge_hub_westend_runtime::bridge_to_rococo_config::WithBridgeHubRococoMessagesInstance,
+>;
+
 decl_test_sender_receiver_accounts_parameter_types! {
 	WestendRelay { sender: ALICE, receiver: BOB },
 	AssetHubWestendPara { sender: ALICE, receiver: BOB },
-	CustomPara { sender: ALICE, receiver: BOB }
+	BridgeHubWestendPara { sender: ALICE, receiver: BOB },
+	CustomPara { sender: ALICE, receiver: BOB },
+	SiblingPara { sender: ALICE, receiver: BOB },
+	DexPara { sender: ALICE, receiver: BOB },
+	RococoRelay { sender: ALICE, receiver: BOB },
+	AssetHubRococoPara { sender: ALICE, receiver: BOB },
+	BridgeHubRococoPara { sender: ALICE, receiver: BOB }
 }
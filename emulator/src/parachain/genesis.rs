@@ -1,14 +1,16 @@
 use emulated_integration_tests_common::build_genesis_storage;
+use parachain_runtime::genesis_config_presets::emulator_genesis;
+use sc_chain_spec::json_merge;
 use sp_runtime::Storage;
 
-pub fn genesis() -> Storage {
-	let genesis_config = parachain_runtime::RuntimeGenesisConfig {
-		parachain_info: parachain_info::GenesisConfig {
-			parachain_id: 2000.into(),
-			..Default::default()
-		},
-		..Default::default()
-	};
+/// Genesis of an instance of the parachain runtime running as `para_id`.
+pub fn genesis(para_id: u32) -> Storage {
+	// The preset is only a patch, so we apply it on top of the default config.
+	let mut config = serde_json::to_value(parachain_runtime::RuntimeGenesisConfig::default())
+		.expect("the default genesis config can be serialized");
+	json_merge(&mut config, emulator_genesis(para_id.into()));
+	let genesis_config: parachain_runtime::RuntimeGenesisConfig =
+		serde_json::from_value(config).expect("the patched genesis config is valid");
 
 	build_genesis_storage(
 		&genesis_config,
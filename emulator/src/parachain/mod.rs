@@ -1,12 +1,93 @@
-mod genesis;
+pub(crate) mod genesis;
 
-use emulated_integration_tests_common::impl_foreign_assets_helpers_for_parachain;
-use xcm_emulator::decl_test_parachains;
+use emulated_integration_tests_common::{
+	impl_accounts_helpers_for_parachain, impl_foreign_assets_helpers_for_parachain,
+};
+use frame_support::traits::OnInitialize;
+use parachain_runtime::configs::xcm_config::{CUSTOM_PARA_ID, SIBLING_PARA_ID};
+use xcm_emulator::{assert_expected_events, decl_test_parachains, Chain, Network};
+
+/// Assertions on the events of the XCM pallet, to be called inside `execute_with`.
+macro_rules! impl_xcm_pallet_assertions {
+	( $chain:ident ) => {
+		impl<N: Network> $chain<N> {
+			/// The program executed with `PolkadotXcm::execute` ran to completion.
+			pub fn assert_xcm_pallet_attempted_complete() {
+				type RuntimeEvent<N> = <$chain<N> as Chain>::RuntimeEvent;
+				assert_expected_events!(
+					Self,
+					vec![
+						RuntimeEvent::<N>::PolkadotXcm(pallet_xcm::Event::Attempted {
+							outcome: xcm::v5::Outcome::Complete { .. },
+						}) => {},
+					]
+				);
+			}
+
+			/// The program executed with `PolkadotXcm::execute` stopped at `index` with
+			/// `error`.
+			pub fn assert_xcm_pallet_attempted_incomplete(index: u8, error: xcm::v5::Error) {
+				type RuntimeEvent<N> = <$chain<N> as Chain>::RuntimeEvent;
+				assert_expected_events!(
+					Self,
+					vec![
+						RuntimeEvent::<N>::PolkadotXcm(pallet_xcm::Event::Attempted {
+							outcome: xcm::v5::Outcome::Incomplete { error: instruction_error, .. },
+						}) => {
+							index: instruction_error.index == index,
+							error: instruction_error.error == error,
+						},
+					]
+				);
+			}
+
+			/// A message was sent to `destination`.
+			pub fn assert_xcm_pallet_sent(destination: xcm::v5::Location) {
+				type RuntimeEvent<N> = <$chain<N> as Chain>::RuntimeEvent;
+				assert_expected_events!(
+					Self,
+					vec![
+						RuntimeEvent::<N>::PolkadotXcm(pallet_xcm::Event::Sent {
+							destination: sent_to,
+							..
+						}) => {
+							destination: *sent_to == destination,
+						},
+					]
+				);
+			}
+		}
+	};
+}
 
 decl_test_parachains! {
 	pub struct Custom {
-		genesis = genesis::genesis(),
-		on_init = {},
+		genesis = genesis::genesis(CUSTOM_PARA_ID),
+		on_init = {
+			parachain_runtime::AuraExt::on_initialize(1);
+		},
+		runtime = parachain_runtime,
+		core = {
+			XcmpMessageHandler: parachain_runtime::XcmpQueue,
+			LocationToAccountId: parachain_runtime::configs::xcm_config::LocationToAccountId,
+			ParachainInfo: parachain_runtime::ParachainInfo,
+			MessageOrigin: cumulus_primitives_core::AggregateMessageOrigin,
+		},
+		pallets = {
+			System: parachain_runtime::System,
+			Balances: parachain_runtime::Balances,
+			ForeignAssets: parachain_runtime::ForeignAssets,
+			PolkadotXcm: parachain_runtime::PolkadotXcm,
+			MessageQueue: parachain_runtime::MessageQueue,
+			XcmpQueue: parachain_runtime::XcmpQueue,
+		}
+	},
+	// A second instance of the same runtime, to try out flows between siblings.
+	pub struct Sibling {
+		genesis = genesis::genesis(SIBLING_PARA_ID),
+		on_init = {
+			parachain_runtime::AuraExt::on_initialize(1);
+		},
 		runtime = parachain_runtime,
 		core = {
 			XcmpMessageHandler: parachain_runtime::XcmpQueue,
@@ -19,8 +100,15 @@ decl_test_parachains! {
 			Balances: parachain_runtime::Balances,
 			ForeignAssets: parachain_runtime::ForeignAssets,
 			PolkadotXcm: parachain_runtime::PolkadotXcm,
+			MessageQueue: parachain_runtime::MessageQueue,
+			XcmpQueue: parachain_runtime::XcmpQueue,
 		}
 	}
 }
 
 impl_foreign_assets_helpers_for_parachain!(Custom, xcm::v5::Location);
+impl_foreign_assets_helpers_for_parachain!(Sibling, xcm::v5::Location);
+impl_accounts_helpers_for_parachain!(Custom);
+impl_accounts_helpers_for_parachain!(Sibling);
+impl_xcm_pallet_assertions!(Custom);
+impl_xcm_pallet_assertions!(Sibling);
@@ -0,0 +1,160 @@
+//! Helpers for processing every message in flight in the network.
+//!
+//! Messages aren't processed right away. The emulator routes them between chains, where they
+//! land in the `MessageQueue` pallet and wait for that chain's next block.
+//! Multi-hop programs therefore need every chain to be poked, possibly several times.
+//!
+//! Both networks are drained together, since messages can cross the bridge between them.
+
+use crate::{
+	capture::CaptureMessages,
+	network::{
+		AssetHubRococoPara, AssetHubWestendPara, BridgeHubRococoPara, BridgeHubWestendPara,
+		CustomPara, DexPara, EvmPara, RococoNetwork, RococoRelay, SiblingPara, WestendNetwork,
+		WestendRelay,
+	},
+	prelude::*,
+};
+
+use cumulus_primitives_core::{AggregateMessageOrigin, ParaId};
+use frame_support::traits::EnqueueMessage;
+use polkadot_runtime_parachains::inclusion::{
+	AggregateMessageOrigin as RelayMessageOrigin, UmpQueueId,
+};
+use xcm_builder::InspectMessageQueues;
+use xcm_emulator::{
+	Network, BRIDGED_MESSAGES, DOWNWARD_MESSAGES, HORIZONTAL_MESSAGES, UPWARD_MESSAGES,
+};
+
+/// Keeps producing blocks on every chain of `WestendNetwork` and `RococoNetwork` until no
+/// UMP, DMP, HRMP or bridged messages remain, neither in transit nor waiting in a
+/// `MessageQueue`.
+///
+/// Each round produces one block on the relay chains and then one on every parachain.
+/// Returns the number of rounds that ran, zero if nothing was pending.
+///
+/// Gives up with [`StillPending`] after `max_rounds`, which usually means a program keeps
+/// bouncing between chains.
+pub fn drain_queues(max_rounds: u32) -> Result<u32, StillPending> {
+	let mut rounds = 0;
+	while has_pending_messages() || waiting_in_bridge_hubs() {
+		if rounds == max_rounds {
+			return Err(StillPending { rounds });
+		}
+		WestendRelay::execute_and_capture(|| {});
+		AssetHubWestendPara::execute_and_capture(|| {});
+		BridgeHubWestendPara::execute_and_capture(|| {});
+		CustomPara::execute_and_capture(|| {});
+		SiblingPara::execute_and_capture(|| {});
+		EvmPara::execute_and_capture(|| {});
+		DexPara::execute_and_capture(|| {});
+		RococoRelay::execute_and_capture(|| {});
+		AssetHubRococoPara::execute_and_capture(|| {});
+		BridgeHubRococoPara::execute_and_capture(|| {});
+		rounds += 1;
+	}
+	Ok(rounds)
+}
+
+/// Messages were still pending after `rounds` rounds of [`drain_queues`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StillPending {
+	pub rounds: u32,
+}
+
+/// Messages coming over the bridge are dispatched outside of a block, so what they send on
+/// waits in the bridge hub's outbound queues until its next block.
+fn waiting_in_bridge_hubs() -> bool {
+	BridgeHubWestendPara::ext_wrapper(|| {
+		has_outbound::<bridge_hub_westend_runtime::xcm_config::XcmRouter>()
+	}) || BridgeHubRococoPara::ext_wrapper(|| {
+		has_outbound::<bridge_hub_rococo_runtime::xcm_config::XcmRouter>()
+	})
+}
+
+/// Whether `Router` of the current chain has messages waiting to be sent.
+fn has_outbound<Router: InspectMessageQueues>() -> bool {
+	Router::get_messages().iter().any(|(_, messages)| !messages.is_empty())
+}
+
+/// Whether any message is waiting to be delivered or processed anywhere in either network.
+pub fn has_pending_messages() -> bool {
+	in_transit(<WestendNetwork as Network>::name()) ||
+		in_transit(<RococoNetwork as Network>::name()) ||
+		queued_in_westend() ||
+		queued_in_rococo()
+}
+
+/// Whether the emulator has messages of the network `name` waiting to be routed.
+fn in_transit(name: &str) -> bool {
+	DOWNWARD_MESSAGES
+		.with(|queues| queues.borrow().get(name).is_some_and(|queue| !queue.is_empty())) ||
+		HORIZONTAL_MESSAGES
+			.with(|queues| queues.borrow().get(name).is_some_and(|queue| !queue.is_empty())) ||
+		UPWARD_MESSAGES
+			.with(|queues| queues.borrow().get(name).is_some_and(|queue| !queue.is_empty())) ||
+		BRIDGED_MESSAGES
+			.with(|queues| queues.borrow().get(name).is_some_and(|queue| !queue.is_empty()))
+}
+
+/// Upward messages from every parachain are queued on the relay chain.
+fn relay_origins<const N: usize>(para_ids: [ParaId; N]) -> [RelayMessageOrigin; N] {
+	para_ids.map(|para_id| RelayMessageOrigin::Ump(UmpQueueId::Para(para_id)))
+}
+
+/// Parachains queue messages from the relay chain and from their siblings.
+fn para_origins<const N: usize>(para_ids: [ParaId; N]) -> Vec<AggregateMessageOrigin> {
+	para_ids
+		.into_iter()
+		.map(AggregateMessageOrigin::Sibling)
+		.chain([AggregateMessageOrigin::Parent])
+		.collect()
+}
+
+fn queued_in_westend() -> bool {
+	let para_ids = [
+		AssetHubWestendPara::para_id(),
+		BridgeHubWestendPara::para_id(),
+		CustomPara::para_id(),
+		SiblingPara::para_id(),
+		EvmPara::para_id(),
+		DexPara::para_id(),
+	];
+	WestendRelay::ext_wrapper(|| {
+		has_queued::<<WestendRelay as Chain>::Runtime, _>(relay_origins(para_ids))
+	}) || AssetHubWestendPara::ext_wrapper(|| {
+		has_queued::<<AssetHubWestendPara as Chain>::Runtime, _>(para_origins(para_ids))
+	}) || BridgeHubWestendPara::ext_wrapper(|| {
+		has_queued::<<BridgeHubWestendPara as Chain>::Runtime, _>(para_origins(para_ids))
+	}) || CustomPara::ext_wrapper(|| {
+		has_queued::<<CustomPara as Chain>::Runtime, _>(para_origins(para_ids))
+	}) || SiblingPara::ext_wrapper(|| {
+		has_queued::<<SiblingPara as Chain>::Runtime, _>(para_origins(para_ids))
+	}) || EvmPara::ext_wrapper(|| {
+		has_queued::<<EvmPara as Chain>::Runtime, _>(para_origins(para_ids))
+	}) || DexPara::ext_wrapper(|| {
+		has_queued::<<DexPara as Chain>::Runtime, _>(para_origins(para_ids))
+	})
+}
+
+fn queued_in_rococo() -> bool {
+	let para_ids = [AssetHubRococoPara::para_id(), BridgeHubRococoPara::para_id()];
+	RococoRelay::ext_wrapper(|| {
+		has_queued::<<RococoRelay as Chain>::Runtime, _>(relay_origins(para_ids))
+	}) || AssetHubRococoPara::ext_wrapper(|| {
+		has_queued::<<AssetHubRococoPara as Chain>::Runtime, _>(para_origins(para_ids))
+	}) || BridgeHubRococoPara::ext_wrapper(|| {
+		has_queued::<<BridgeHubRococoPara as Chain>::Runtime, _>(para_origins(para_ids))
+	})
+}
+
+/// Whether the `MessageQueue` of the current chain has messages from any of `origins`.
+fn has_queued<T, O>(origins: impl IntoIterator<Item = O>) -> bool
+where
+	T: pallet_message_queue::Config,
+	pallet_message_queue::Pallet<T>: EnqueueMessage<O>,
+{
+	origins.into_iter().any(|origin| {
+		<pallet_message_queue::Pallet<T> as EnqueueMessage<O>>::footprint(origin).storage.count > 0
+	})
+}
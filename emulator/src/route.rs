@@ -0,0 +1,446 @@
+//! Plans transfers that go through several chains, with the fees of every hop sized for us.
+//!
+//! `transfer_n_times` nests `InitiateTransfer`s by hand and guesses what every hop costs.
+//! Here we give the chains to go through and the asset each of them is paid with, and every
+//! chain is asked with its `XcmPaymentApi` what it'll charge: the weight of what it executes
+//! and the delivery of what it sends on.
+//!
+//! ```ignore
+//! let route = RoutePlanner::from(Parachain(2000))
+//! 	.asset((Parachain(2000), 1 * PARA_UNITS))
+//! 	.fee_asset(Parachain(2000))
+//! 	.hop(Parachain(1000), Here)
+//! 	.hop(Parachain(2000), Parachain(2000))
+//! 	.beneficiary(receiver)
+//! 	.plan::<<CustomPara as Chain>::RuntimeCall>()?;
+//! ```
+//!
+//! Assets are relative to the relay chain, like chains, so `Here` is `WND` and `Parachain(id)`
+//! the native token of a parachain. Fee assets other than the one transferred are withdrawn on
+//! the origin too, and travel along until they're spent.
+//!
+//! Every chain of the Westend network can be asked, the bridged Rococo ones can't.
+//!
+//! Fees depend on the size of the messages, which depends on the fees, so they're sized again
+//! until they don't change. Delivery fees have to be charged in the asset paying for the hop,
+//! we don't plan swaps.
+
+use crate::{
+	bridge_hub_westend::genesis::PARA_ID as BRIDGE_HUB_ID,
+	network::{
+		AssetHubWestendPara, BridgeHubWestendPara, CustomPara, DexPara, EvmPara, SiblingPara,
+		WestendRelay,
+	},
+	transfer::{filter, TransferError, TrustTable},
+};
+
+use dex_parachain_runtime::configs::xcm_config::DEX_PARA_ID;
+use evm_parachain_runtime::configs::xcm_config::EVM_PARA_ID;
+use frame_support::weights::Weight;
+use parachain_runtime::configs::xcm_config::{ASSET_HUB_ID, CUSTOM_PARA_ID, SIBLING_PARA_ID};
+use std::{collections::BTreeMap, iter};
+use xcm::{latest::AssetTransferFilter, prelude::*, VersionedAssetId};
+use xcm_emulator::TestExt;
+use xcm_executor::traits::TransferType;
+use xcm_runtime_apis::fees::{
+	runtime_decl_for_xcm_payment_api::XcmPaymentApiV1, Error as XcmPaymentApiError,
+};
+
+/// How many times fees are sized before giving up.
+const MAX_ROUNDS: usize = 10;
+
+/// Amounts of fungible assets, relative to the relay chain.
+type Holding = BTreeMap<Location, u128>;
+
+/// Runs `$body` on the emulated chain at `$chain`, with `$runtime` as its runtime.
+///
+/// Only the chain's storage is touched: no block is built, so queued messages stay queued.
+macro_rules! on_chain {
+	($chain:expr, |$runtime:ident| $body:expr) => {
+		match $chain.unpack() {
+			(0, []) => Ok(WestendRelay::ext_wrapper(|| {
+				type $runtime = westend_runtime::Runtime;
+				$body
+			})),
+			(0, [Parachain(ASSET_HUB_ID)]) => Ok(AssetHubWestendPara::ext_wrapper(|| {
+				type $runtime = asset_hub_westend_runtime::Runtime;
+				$body
+			})),
+			(0, [Parachain(BRIDGE_HUB_ID)]) => Ok(BridgeHubWestendPara::ext_wrapper(|| {
+				type $runtime = bridge_hub_westend_runtime::Runtime;
+				$body
+			})),
+			(0, [Parachain(CUSTOM_PARA_ID)]) => Ok(CustomPara::ext_wrapper(|| {
+				type $runtime = parachain_runtime::Runtime;
+				$body
+			})),
+			(0, [Parachain(SIBLING_PARA_ID)]) => Ok(SiblingPara::ext_wrapper(|| {
+				type $runtime = parachain_runtime::Runtime;
+				$body
+			})),
+			(0, [Parachain(EVM_PARA_ID)]) => Ok(EvmPara::ext_wrapper(|| {
+				type $runtime = evm_parachain_runtime::Runtime;
+				$body
+			})),
+			(0, [Parachain(DEX_PARA_ID)]) => Ok(DexPara::ext_wrapper(|| {
+				type $runtime = dex_parachain_runtime::Runtime;
+				$body
+			})),
+			_ => Err(RouteError::UnsupportedChain($chain.clone())),
+		}
+	};
+}
+
+/// Why a route couldn't be planned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RouteError {
+	/// A parameter without a default wasn't given.
+	Missing(&'static str),
+	/// Only fungible assets can be planned for.
+	NotFungible,
+	/// There's no emulated chain we can ask for fees at this location.
+	UnsupportedChain(Location),
+	/// A chain couldn't size its fees.
+	Payment { chain: Location, error: XcmPaymentApiError },
+	/// A chain charges delivery fees in another asset than the one paying for its hop.
+	DeliveryFeeAsset { chain: Location, asset: AssetId },
+	/// There's not enough left to pay this chain.
+	NotEnoughForFees { chain: Location },
+	/// Fees kept changing with the size of the messages.
+	FeesDidNotSettle,
+	Transfer(TransferError),
+}
+
+impl From<TransferError> for RouteError {
+	fn from(error: TransferError) -> Self {
+		Self::Transfer(error)
+	}
+}
+
+/// A planned route.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Route<Call> {
+	/// The program to execute on the origin.
+	pub xcm: Xcm<Call>,
+	/// What each chain charges, starting with the origin, relative to the relay chain.
+	pub fees: Vec<Asset>,
+	/// What reaches the beneficiary, relative to the relay chain.
+	pub delivered: Assets,
+}
+
+impl<Call> Route<Call> {
+	/// The fees charged in `asset` over the whole route.
+	pub fn fees_in(&self, asset: impl Into<Location>) -> u128 {
+		let id = AssetId(asset.into());
+		self.fees
+			.iter()
+			.filter(|fee| fee.id == id)
+			.map(|fee| match fee.fun {
+				Fungible(amount) => amount,
+				NonFungible(_) => 0,
+			})
+			.sum()
+	}
+}
+
+/// Plans a transfer of one asset through a list of chains.
+#[derive(Clone, Debug)]
+pub struct RoutePlanner {
+	table: TrustTable,
+	origin: Location,
+	asset: Option<Asset>,
+	fee_asset: Option<Location>,
+	hops: Vec<(Location, Location)>,
+	beneficiary: Option<Location>,
+}
+
+impl RoutePlanner {
+	/// A route from `origin`, using the trust of the Westend network.
+	pub fn from(origin: impl Into<Location>) -> Self {
+		Self {
+			table: TrustTable::westend(),
+			origin: origin.into(),
+			asset: None,
+			fee_asset: None,
+			hops: Vec::new(),
+			beneficiary: None,
+		}
+	}
+
+	pub fn trust_table(mut self, table: TrustTable) -> Self {
+		self.table = table;
+		self
+	}
+
+	/// The asset to transfer.
+	pub fn asset(mut self, asset: impl Into<Asset>) -> Self {
+		self.asset = Some(asset.into());
+		self
+	}
+
+	/// The asset paying for executing on the origin.
+	pub fn fee_asset(mut self, asset: impl Into<Location>) -> Self {
+		self.fee_asset = Some(asset.into());
+		self
+	}
+
+	/// The next chain to go to, and the asset paying for executing there.
+	/// The last one is the destination.
+	pub fn hop(mut self, chain: impl Into<Location>, fee_asset: impl Into<Location>) -> Self {
+		self.hops.push((chain.into(), fee_asset.into()));
+		self
+	}
+
+	/// Gets what's left on the destination.
+	pub fn beneficiary(mut self, beneficiary: impl Into<Location>) -> Self {
+		self.beneficiary = Some(beneficiary.into());
+		self
+	}
+
+	/// Sizes the fees of every chain and builds the program that pays them.
+	pub fn plan<Call>(&self) -> Result<Route<Call>, RouteError> {
+		let fee_asset = self.fee_asset.clone().ok_or(RouteError::Missing("fee asset"))?;
+		if self.hops.is_empty() {
+			return Err(RouteError::Missing("hops"));
+		}
+		let chains: Vec<Location> = iter::once(self.origin.clone())
+			.chain(self.hops.iter().map(|(chain, _)| chain.clone()))
+			.collect();
+		let fee_assets: Vec<Location> = iter::once(fee_asset)
+			.chain(self.hops.iter().map(|(_, fee_asset)| fee_asset.clone()))
+			.collect();
+
+		let mut fees = vec![0; chains.len()];
+		for _ in 0..MAX_ROUNDS {
+			let (mut programs, delivered) = self.programs(&chains, &fee_assets, &fees)?;
+			let sized = size_fees(&chains, &fee_assets, &programs)?;
+			if sized == fees {
+				return Ok(Route {
+					xcm: programs.swap_remove(0).into(),
+					fees: fee_assets.into_iter().zip(fees).map(Asset::from).collect(),
+					delivered: delivered.into_iter().map(Asset::from).collect::<Vec<_>>().into(),
+				});
+			}
+			fees = sized;
+		}
+		Err(RouteError::FeesDidNotSettle)
+	}
+
+	/// What every chain executes with these fees, and what's delivered in the end.
+	///
+	/// The origin executes the program we build. Every other chain executes what the one before
+	/// sends: what `InitiateTransfer` puts in front of the remote XCM, and the topic.
+	fn programs(
+		&self,
+		chains: &[Location],
+		fee_assets: &[Location],
+		fees: &[u128],
+	) -> Result<(Vec<Xcm<()>>, Holding), RouteError> {
+		let asset = self.asset.clone().ok_or(RouteError::Missing("asset"))?;
+		let beneficiary = self.beneficiary.clone().ok_or(RouteError::Missing("beneficiary"))?;
+		let Fungible(amount) = asset.fun else { return Err(RouteError::NotFungible) };
+
+		// Fees paid with other assets are withdrawn with the asset.
+		let mut withdrawn = Holding::from([(asset.id.0.clone(), amount)]);
+		for (fee_asset, fee) in fee_assets.iter().zip(fees) {
+			if *fee_asset != asset.id.0 {
+				*withdrawn.entry(fee_asset.clone()).or_default() += fee;
+			}
+		}
+		withdrawn.retain(|_, amount| *amount > 0);
+		// What's left in holding on every chain, once it's paid.
+		let mut holding = withdrawn.clone();
+		let mut holdings = Vec::with_capacity(chains.len());
+		for ((chain, fee_asset), fee) in chains.iter().zip(fee_assets).zip(fees) {
+			let left = holding
+				.get(fee_asset)
+				.copied()
+				.unwrap_or_default()
+				.checked_sub(*fee)
+				.ok_or_else(|| RouteError::NotEnoughForFees { chain: chain.clone() })?;
+			holding.insert(fee_asset.clone(), left);
+			holding.retain(|_, amount| *amount > 0);
+			holdings.push(holding.clone());
+		}
+
+		// We start from the destination and wrap every hop around the one after it.
+		let last = chains.len() - 1;
+		let mut programs = vec![Xcm::new(); chains.len()];
+		let mut remote_xcm = Xcm::<()>::builder_unsafe()
+			.deposit_asset(AllCounted(holdings[last].len() as u32), beneficiary)
+			.build();
+		for hop in (1..=last).rev() {
+			let (from, to) = (&chains[hop - 1], &chains[hop]);
+			let fee = (fee_assets[hop].clone(), fees[hop]);
+			let fee_type = self.table.transfer_type(from, to, &fee.0)?;
+			let groups = self.groups(from, to, &holdings[hop])?;
+
+			let mut received = vec![
+				arrival(fee_type.clone(), seen_from(to, [(&fee.0, &fee.1)])?),
+				PayFees { asset: (reanchor(&fee.0, to)?, fee.1).into() },
+			];
+			for (transfer_type, assets) in &groups {
+				received.push(arrival(transfer_type.clone(), seen_from(to, assets)?));
+			}
+			received.push(ClearOrigin);
+			received.extend(remote_xcm.inner().iter().cloned());
+			// The router sets a topic if there's none.
+			received.push(SetTopic([0; 32]));
+			programs[hop] = Xcm(received);
+
+			remote_xcm = Xcm::<()>::builder_unsafe()
+				.initiate_transfer(
+					reanchor(to, from)?,
+					filter(fee_type, Definite(seen_from(from, [(&fee.0, &fee.1)])?)),
+					false,
+					filters(from, &groups)?,
+					remote_xcm,
+				)
+				.build();
+		}
+		let mut local = Xcm::<()>::builder_unsafe()
+			.withdraw_asset(seen_from(&chains[0], &withdrawn)?)
+			.pay_fees((reanchor(&fee_assets[0], &chains[0])?, fees[0]))
+			.build();
+		local.inner_mut().extend(remote_xcm);
+		programs[0] = local;
+
+		Ok((programs, holdings[last].clone()))
+	}
+
+	/// Assets arriving on `to`, grouped by how they travel from `from`.
+	///
+	/// Like `TransferBuilder`, a single group takes everything if they all travel the same way.
+	/// Otherwise, each asset is in its own.
+	fn groups(
+		&self,
+		from: &Location,
+		to: &Location,
+		arriving: &Holding,
+	) -> Result<Vec<(TransferType, Holding)>, RouteError> {
+		let groups = arriving
+			.iter()
+			.map(|(asset, amount)| -> Result<_, RouteError> {
+				let transfer_type = self.table.transfer_type(from, to, asset)?;
+				Ok((transfer_type, Holding::from([(asset.clone(), *amount)])))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let same_way = groups.windows(2).all(|pair| pair[0].0 == pair[1].0);
+		if let (true, Some((transfer_type, _))) = (same_way, groups.first()) {
+			return Ok(vec![(transfer_type.clone(), arriving.clone())]);
+		}
+		Ok(groups)
+	}
+}
+
+/// Asks every chain what it charges for executing its program and sending the next one.
+fn size_fees(
+	chains: &[Location],
+	fee_assets: &[Location],
+	programs: &[Xcm<()>],
+) -> Result<Vec<u128>, RouteError> {
+	chains
+		.iter()
+		.enumerate()
+		.map(|(hop, chain)| -> Result<u128, RouteError> {
+			let fee_asset = reanchor(&fee_assets[hop], chain)?;
+			let weight = query_xcm_weight(chain, &programs[hop])?;
+			let mut fee = query_weight_to_asset_fee(chain, weight, &fee_asset)?;
+			if let Some(next) = chains.get(hop + 1) {
+				let destination = reanchor(next, chain)?;
+				let delivery_fees = query_delivery_fees(chain, &destination, &programs[hop + 1])?;
+				for delivery_fee in delivery_fees.into_inner() {
+					match delivery_fee {
+						Asset { id, fun: Fungible(amount) } if id.0 == fee_asset => fee += amount,
+						Asset { id, .. } => {
+							let chain = chain.clone();
+							return Err(RouteError::DeliveryFeeAsset { chain, asset: id });
+						},
+					}
+				}
+			}
+			Ok(fee)
+		})
+		.collect()
+}
+
+fn query_xcm_weight(chain: &Location, xcm: &Xcm<()>) -> Result<Weight, RouteError> {
+	let xcm = VersionedXcm::from(xcm.clone());
+	on_chain!(chain, |Runtime| Runtime::query_xcm_weight(xcm))?
+		.map_err(|error| RouteError::Payment { chain: chain.clone(), error })
+}
+
+fn query_weight_to_asset_fee(
+	chain: &Location,
+	weight: Weight,
+	asset: &Location,
+) -> Result<u128, RouteError> {
+	let asset = VersionedAssetId::from(AssetId(asset.clone()));
+	on_chain!(chain, |Runtime| Runtime::query_weight_to_asset_fee(weight, asset))?
+		.map_err(|error| RouteError::Payment { chain: chain.clone(), error })
+}
+
+fn query_delivery_fees(
+	chain: &Location,
+	destination: &Location,
+	xcm: &Xcm<()>,
+) -> Result<Assets, RouteError> {
+	let destination = VersionedLocation::from(destination.clone());
+	let xcm = VersionedXcm::from(xcm.clone());
+	let fees = on_chain!(chain, |Runtime| Runtime::query_delivery_fees(destination, xcm))?
+		.map_err(|error| RouteError::Payment { chain: chain.clone(), error })?;
+	Assets::try_from(fees).map_err(|_| RouteError::Payment {
+		chain: chain.clone(),
+		error: XcmPaymentApiError::VersionedConversionFailed,
+	})
+}
+
+/// The instruction `InitiateTransfer` puts in front of assets travelling this way.
+fn arrival(transfer_type: TransferType, assets: Assets) -> Instruction<()> {
+	match transfer_type {
+		TransferType::Teleport => ReceiveTeleportedAsset(assets),
+		TransferType::LocalReserve => ReserveAssetDeposited(assets),
+		TransferType::DestinationReserve => WithdrawAsset(assets),
+		TransferType::RemoteReserve(_) => unreachable!("no remote reserves in the trust table"),
+	}
+}
+
+/// One filter per group, as seen from `from`.
+fn filters(
+	from: &Location,
+	groups: &[(TransferType, Holding)],
+) -> Result<Vec<AssetTransferFilter>, RouteError> {
+	groups
+		.iter()
+		.map(|(transfer_type, assets)| -> Result<_, RouteError> {
+			let assets = match &assets.keys().collect::<Vec<_>>()[..] {
+				[asset] if groups.len() > 1 =>
+					Wild(AllOf { id: AssetId(reanchor(asset, from)?), fun: WildFungible }),
+				_ => Wild(AllCounted(assets.len() as u32)),
+			};
+			Ok(filter(transfer_type.clone(), assets))
+		})
+		.collect()
+}
+
+/// `assets`, relative to the relay chain, as seen from `chain`.
+fn seen_from<'a>(
+	chain: &Location,
+	assets: impl IntoIterator<Item = (&'a Location, &'a u128)>,
+) -> Result<Assets, RouteError> {
+	let assets = assets
+		.into_iter()
+		.map(|(id, amount)| -> Result<Asset, RouteError> {
+			Ok((reanchor(id, chain)?, *amount).into())
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	Ok(assets.into())
+}
+
+/// `location`, relative to the relay chain, as seen from `chain`.
+fn reanchor(location: &Location, chain: &Location) -> Result<Location, RouteError> {
+	location
+		.clone()
+		.reanchored(chain, &Here)
+		.map_err(|location| TransferError::OutsideOfNetwork(location).into())
+}
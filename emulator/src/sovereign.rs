@@ -0,0 +1,92 @@
+//! Which account a location gets on each chain of the network.
+//!
+//! Chains turn locations into accounts with a stack of `ConvertLocation` implementations,
+//! and every chain has its own stack. This module runs the stacks of our chains outside of
+//! the emulator, so we can see the account a location gets without building a network.
+
+use core::{fmt, str::FromStr};
+
+use sp_core::crypto::{Ss58AddressFormat, Ss58Codec};
+use sp_runtime::AccountId32;
+use xcm::prelude::*;
+use xcm_executor::traits::ConvertLocation;
+
+/// The SS58 prefix of Westend and its parachains.
+pub const WESTEND_SS58_PREFIX: u16 = 42;
+
+/// The converter stacks of our chains.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Converter {
+	/// `CustomPara` and `SiblingPara`: the hash of the location's description, local
+	/// `AccountId32`s as they are and the hash of local `AccountKey20`s.
+	CustomPara,
+	/// Asset Hub Westend.
+	AssetHub,
+	/// The Westend relay chain, which gives its parachains an account with
+	/// `ChildParachainConvertsVia`.
+	Relay,
+}
+
+impl Converter {
+	pub const ALL: [Converter; 3] = [Converter::CustomPara, Converter::AssetHub, Converter::Relay];
+
+	/// The account `location` gets, seen from the chain with this stack.
+	/// `None` when no converter in the stack knows the location.
+	pub fn convert(&self, location: &Location) -> Option<SovereignAccount> {
+		type CustomParaStack = parachain_runtime::configs::xcm_config::LocationToAccountId;
+		type AssetHubStack = asset_hub_westend_runtime::xcm_config::LocationToAccountId;
+		type RelayStack = westend_runtime::xcm_config::LocationConverter;
+
+		match self {
+			Converter::CustomPara =>
+				<CustomParaStack as ConvertLocation<AccountId32>>::convert_location(location),
+			Converter::AssetHub =>
+				<AssetHubStack as ConvertLocation<AccountId32>>::convert_location(location),
+			Converter::Relay =>
+				<RelayStack as ConvertLocation<AccountId32>>::convert_location(location),
+		}
+		.map(SovereignAccount)
+	}
+}
+
+impl fmt::Display for Converter {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let name = match self {
+			Converter::CustomPara => "custom-para",
+			Converter::AssetHub => "asset-hub",
+			Converter::Relay => "relay",
+		};
+		write!(f, "{name}")
+	}
+}
+
+impl FromStr for Converter {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		Converter::ALL
+			.into_iter()
+			.find(|converter| converter.to_string() == name)
+			.ok_or_else(|| {
+				format!("unknown converter `{name}`, expected custom-para, asset-hub or relay")
+			})
+	}
+}
+
+/// An account derived from a location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SovereignAccount(pub AccountId32);
+
+impl SovereignAccount {
+	/// The raw 32 bytes as hex.
+	pub fn hex(&self) -> String {
+		let bytes: &[u8] = self.0.as_ref();
+		let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+		format!("0x{hex}")
+	}
+
+	/// The address wallets show for a network with `prefix`.
+	pub fn ss58(&self, prefix: u16) -> String {
+		self.0.to_ss58check_with_version(Ss58AddressFormat::custom(prefix))
+	}
+}
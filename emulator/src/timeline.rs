@@ -0,0 +1,241 @@
+//! Follows a message across chains using its topic.
+//!
+//! `XcmRouter`s wrap messages with `WithUniqueTopic`, so every message ends with a `SetTopic`.
+//! The topic is kept when a message is forwarded, and the `TrailingSetTopicAsId` barrier
+//! makes it the id in `MessageQueue::Processed`. We use it to tie together what happened
+//! on each chain.
+//!
+//! Events are only observed in blocks run with `execute_and_capture` or `drain_queues`.
+//!
+//! Fees are priced with the `XcmPaymentApi` of each chain, for the message they belong to:
+//! the weight a chain used for a message in the asset the message paid fees with, and the
+//! delivery of each message it sent on. Both need the message to be captured, so fees are
+//! missing for messages from blocks that weren't, and for the ones relayed over the bridge.
+//! The chain where the journey started executed a local program, which is never priced.
+//! They're estimates: what a `Trader` actually took isn't in the events, and a message can
+//! pay more than the weight it used.
+
+use crate::prelude::*;
+
+use core::{cell::RefCell, fmt};
+use frame_support::weights::Weight;
+use sp_runtime::SaturatedConversion;
+use xcm::{prelude::*, VersionedAssetId, VersionedAssets};
+
+thread_local! {
+	static OBSERVED: RefCell<Vec<ObservedBlock>> = RefCell::new(Vec::new());
+}
+
+/// What a chain did with messages in one block.
+struct ObservedBlock {
+	chain: Location,
+	block: u32,
+	/// `MessageQueue::Processed` events, by topic.
+	processed: Vec<(XcmHash, Processed)>,
+	/// `PolkadotXcm::Sent` events: topic, destination and delivery fees.
+	sent: Vec<(XcmHash, Location, Vec<Asset>)>,
+}
+
+/// The `XcmPaymentApi` of a chain.
+#[derive(Clone, Copy)]
+pub(crate) struct Pricing {
+	pub weight_to_fee: fn(Weight, VersionedAssetId) -> Option<u128>,
+	pub delivery_fees: fn(VersionedLocation, VersionedXcm<()>) -> Option<VersionedAssets>,
+}
+
+/// How a chain processed an incoming message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Processed {
+	pub success: bool,
+	pub weight_used: Weight,
+	/// What the chain would charge for `weight_used`, in the asset the message paid fees with.
+	/// `None` if the message wasn't captured or the chain can't price that asset.
+	pub estimated_execution_fee: Option<Asset>,
+}
+
+/// A single step of a message's journey: one chain, one block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hop {
+	/// The chain, from the relay chain's point of view.
+	pub chain: Location,
+	pub block: u32,
+	/// `None` on the chain where the journey started.
+	pub processed: Option<Processed>,
+	/// Where the message was forwarded to, from this chain's point of view.
+	pub sent_to: Vec<Location>,
+	/// What delivering the messages in `sent_to` would cost on this chain.
+	pub estimated_delivery_fees: Vec<Asset>,
+}
+
+impl fmt::Display for Hop {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?} at block {}", self.chain, self.block)?;
+		match &self.processed {
+			Some(Processed { success: true, weight_used, .. }) =>
+				write!(f, ": processed, {weight_used:?}")?,
+			Some(Processed { success: false, weight_used, .. }) =>
+				write!(f, ": FAILED, {weight_used:?}")?,
+			None => write!(f, ": started")?,
+		}
+		if let Some(Processed { estimated_execution_fee: Some(fee), .. }) = &self.processed {
+			write!(f, "\n    ~{fee:?} for execution (estimated)")?;
+		}
+		for destination in &self.sent_to {
+			write!(f, "\n    sent to {destination:?}")?;
+		}
+		for fee in &self.estimated_delivery_fees {
+			write!(f, "\n    ~{fee:?} for delivery (estimated)")?;
+		}
+		Ok(())
+	}
+}
+
+/// Every hop of a message, in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Timeline {
+	pub topic: XcmHash,
+	pub hops: Vec<Hop>,
+}
+
+impl Timeline {
+	/// Gathers every observed hop of the message with `topic`.
+	pub fn follow(topic: XcmHash) -> Self {
+		let hops = OBSERVED.with(|observed| {
+			observed
+				.borrow()
+				.iter()
+				.filter_map(|block| {
+					let processed = block
+						.processed
+						.iter()
+						.find(|(id, _)| *id == topic)
+						.map(|(_, processed)| processed.clone());
+					let sent: Vec<_> = block.sent.iter().filter(|(id, ..)| *id == topic).collect();
+					if processed.is_none() && sent.is_empty() {
+						return None;
+					}
+					Some(Hop {
+						chain: block.chain.clone(),
+						block: block.block,
+						processed,
+						sent_to: sent
+							.iter()
+							.map(|(_, destination, _)| destination.clone())
+							.collect(),
+						estimated_delivery_fees: sent
+							.iter()
+							.flat_map(|(.., fees)| fees.clone())
+							.collect(),
+					})
+				})
+				.collect()
+		});
+		Self { topic, hops }
+	}
+
+	/// Whether every chain processed the message successfully.
+	pub fn is_success(&self) -> bool {
+		self.hops
+			.iter()
+			.all(|hop| hop.processed.as_ref().is_none_or(|processed| processed.success))
+	}
+
+	/// The total weight used by every chain that processed the message.
+	pub fn weight_used(&self) -> Weight {
+		self.hops
+			.iter()
+			.filter_map(|hop| hop.processed.as_ref())
+			.fold(Weight::zero(), |total, processed| total.saturating_add(processed.weight_used))
+	}
+}
+
+impl fmt::Display for Timeline {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "topic 0x{}", sp_core::hexdisplay::HexDisplay::from(&self.topic))?;
+		for (index, hop) in self.hops.iter().enumerate() {
+			write!(f, "\n#{index} {hop}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Forgets every observed block.
+pub fn clear_timelines() {
+	OBSERVED.with(|observed| observed.borrow_mut().clear());
+}
+
+/// Records the XCM related events of the current block of `chain`, after the messages it
+/// sent were captured.
+pub(crate) fn observe<T>(chain: Location, pricing: Pricing)
+where
+	T: pallet_message_queue::Config + pallet_xcm::Config,
+	<T as frame_system::Config>::RuntimeEvent:
+		TryInto<pallet_message_queue::Event<T>> + TryInto<pallet_xcm::Event<T>>,
+{
+	let mut observed = ObservedBlock {
+		chain,
+		block: frame_system::Pallet::<T>::block_number().saturated_into(),
+		processed: Vec::new(),
+		sent: Vec::new(),
+	};
+	for record in frame_system::Pallet::<T>::events() {
+		let message_queue_event: Result<pallet_message_queue::Event<T>, _> =
+			record.event.clone().try_into();
+		if let Ok(pallet_message_queue::Event::Processed { id, weight_used, success, .. }) =
+			message_queue_event
+		{
+			let id = id.into();
+			let estimated_execution_fee = execution_fee(&observed.chain, id, weight_used, pricing);
+			observed
+				.processed
+				.push((id, Processed { success, weight_used, estimated_execution_fee }));
+		}
+		let xcm_event: Result<pallet_xcm::Event<T>, _> = record.event.try_into();
+		if let Ok(pallet_xcm::Event::Sent { destination, message_id, .. }) = xcm_event {
+			let fees = delivery_fees(&observed.chain, &destination, message_id, pricing);
+			observed.sent.push((message_id, destination, fees));
+		}
+	}
+	OBSERVED.with(|blocks| blocks.borrow_mut().push(observed));
+}
+
+/// What `chain` charges for `weight_used`, in the asset the last message with `topic` that
+/// was captured on its way there paid fees with.
+fn execution_fee(
+	chain: &Location,
+	topic: XcmHash,
+	weight_used: Weight,
+	pricing: Pricing,
+) -> Option<Asset> {
+	let message = MessageLog::get().to(chain.clone()).with_topic(topic).into_iter().last()?;
+	let id = message.instructions().into_iter().find_map(|instruction| match instruction {
+		PayFees { asset } | BuyExecution { fees: asset, .. } => Some(asset.id),
+		_ => None,
+	})?;
+	let amount = (pricing.weight_to_fee)(weight_used, id.clone().into())?;
+	Some((id, amount).into())
+}
+
+/// What `chain` charges for delivering the message with `topic` it sent to `destination`.
+fn delivery_fees(
+	chain: &Location,
+	destination: &Location,
+	topic: XcmHash,
+	pricing: Pricing,
+) -> Vec<Asset> {
+	// Captured messages are addressed from the relay chain's point of view.
+	let Ok(to) = chain.clone().appended_with(destination.clone()) else { return Vec::new() };
+	let Some(message) = MessageLog::get()
+		.from(chain.clone())
+		.to(to)
+		.with_topic(topic)
+		.into_iter()
+		.last()
+	else {
+		return Vec::new();
+	};
+	(pricing.delivery_fees)(destination.clone().into(), message.message)
+		.and_then(|fees| Assets::try_from(fees).ok())
+		.map(Assets::into_inner)
+		.unwrap_or_default()
+}
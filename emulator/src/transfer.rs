@@ -0,0 +1,364 @@
+//! Builds the program for a transfer between two chains of the network.
+//!
+//! Every asset of an `InitiateTransfer` needs a transfer type, and the right one depends on
+//! who trusts whom: the destination has to accept it, or the assets are trapped there.
+//! The builder looks each asset up in a `TrustTable`, which mirrors the `IsReserve` and
+//! `IsTeleporter` of the chains, and picks the type for us.
+//!
+//! ```ignore
+//! // Withdraws 1 PARA and 0.1 WND. The local fee is paid out of the PARA, so at most 0.9 PARA
+//! // arrives.
+//! let xcm: Xcm<<CustomPara as Chain>::RuntimeCall> = TransferBuilder::from(Parachain(2000))
+//! 	.to(Parachain(1000))
+//! 	.asset((Here, 1 * PARA_UNITS))
+//! 	.fee((Here, 10 * PARA_CENTS))
+//! 	.remote_fee((Parent, 10 * WND_CENTS))
+//! 	.beneficiary(receiver)
+//! 	.build()?;
+//! ```
+//!
+//! Chains are given by their location relative to the relay chain, so `Here` is the relay
+//! and `Parachain(id)` a parachain. Assets are given as seen from the origin, like in the
+//! program that's built.
+
+use crate::bridge_hub_westend::genesis::PARA_ID as BRIDGE_HUB_ID;
+
+use dex_parachain_runtime::configs::xcm_config::DEX_PARA_ID;
+use evm_parachain_runtime::configs::xcm_config::EVM_PARA_ID;
+use parachain_runtime::configs::xcm_config::{ASSET_HUB_ID, CUSTOM_PARA_ID, SIBLING_PARA_ID};
+use xcm::{latest::AssetTransferFilter, prelude::*};
+use xcm_executor::traits::TransferType;
+
+/// What a chain trusts another one with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trust {
+	/// The other chain holds the asset, what arrives from it is backed by what it holds.
+	Reserve,
+	/// The other chain burns the asset when sending it, so it can be minted here.
+	Teleporter,
+}
+
+/// A chain accepts `asset` from `from`, as a reserve or a teleporter.
+/// Every location is relative to the relay chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrustEntry {
+	/// The chain whose `IsReserve` or `IsTeleporter` this is part of.
+	pub chain: Location,
+	pub asset: Location,
+	pub from: Location,
+	pub trust: Trust,
+}
+
+/// The trust relationships of a network.
+#[derive(Clone, Debug, Default)]
+pub struct TrustTable {
+	entries: Vec<TrustEntry>,
+}
+
+impl TrustTable {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The chains of the emulated Westend network, as they are configured.
+	pub fn westend() -> Self {
+		let wnd = Location::here();
+		let relay = Location::here();
+		let asset_hub = Location::from(Parachain(ASSET_HUB_ID));
+		let bridge_hub = Location::from(Parachain(BRIDGE_HUB_ID));
+		let system_chains = [relay.clone(), asset_hub.clone(), bridge_hub.clone()];
+		// Parachains, whose location is also the one of their native token.
+		let parachains: Vec<Location> = [CUSTOM_PARA_ID, SIBLING_PARA_ID, EVM_PARA_ID, DEX_PARA_ID]
+			.into_iter()
+			.map(|id| Parachain(id).into())
+			.collect();
+
+		let mut table = Self::new();
+		// The system chains teleport `WND` between each other.
+		for chain in &system_chains {
+			for from in system_chains.iter().filter(|from| *from != chain) {
+				table = table.teleporter(chain.clone(), wnd.clone(), from.clone());
+			}
+		}
+		// The asset hub takes teleports of the native token of any parachain.
+		for para in &parachains {
+			table = table.teleporter(asset_hub.clone(), para.clone(), para.clone());
+		}
+		for chain in &parachains {
+			// `RelayAssetFrom<AssetHubLocation>`.
+			table = table.reserve(chain.clone(), wnd.clone(), asset_hub.clone());
+			// `SiblingNativeAsset`.
+			for sibling in parachains.iter().filter(|sibling| *sibling != chain) {
+				table = table.reserve(chain.clone(), sibling.clone(), sibling.clone());
+			}
+		}
+		// Only the `parachain` runtime trusts the asset hub with more.
+		let instances = [CUSTOM_PARA_ID, SIBLING_PARA_ID].map(|id| Location::from(Parachain(id)));
+		for chain in &instances {
+			// `OtherInstanceTokenFrom<AssetHubLocation>`.
+			for other in instances.iter().filter(|other| *other != chain) {
+				table = table.reserve(chain.clone(), other.clone(), asset_hub.clone());
+			}
+			// `NativeAssetFrom<AssetHubLocation>`.
+			table = table.teleporter(chain.clone(), chain.clone(), asset_hub.clone());
+		}
+		table
+	}
+
+	/// `chain` takes `asset` from `from` as a reserve.
+	pub fn reserve(
+		self,
+		chain: impl Into<Location>,
+		asset: impl Into<Location>,
+		from: impl Into<Location>,
+	) -> Self {
+		self.with(TrustEntry {
+			chain: chain.into(),
+			asset: asset.into(),
+			from: from.into(),
+			trust: Trust::Reserve,
+		})
+	}
+
+	/// `chain` takes `asset` from `from` as a teleporter.
+	pub fn teleporter(
+		self,
+		chain: impl Into<Location>,
+		asset: impl Into<Location>,
+		from: impl Into<Location>,
+	) -> Self {
+		self.with(TrustEntry {
+			chain: chain.into(),
+			asset: asset.into(),
+			from: from.into(),
+			trust: Trust::Teleporter,
+		})
+	}
+
+	pub fn with(mut self, entry: TrustEntry) -> Self {
+		self.entries.push(entry);
+		self
+	}
+
+	pub fn entries(&self) -> &[TrustEntry] {
+		&self.entries
+	}
+
+	/// Whether `chain` takes `asset` from `from` as a `trust`.
+	pub fn trusts(
+		&self,
+		chain: &Location,
+		asset: &Location,
+		from: &Location,
+		trust: Trust,
+	) -> bool {
+		self.entries.iter().any(|entry| {
+			&entry.chain == chain &&
+				&entry.asset == asset &&
+				&entry.from == from &&
+				entry.trust == trust
+		})
+	}
+
+	/// How `asset` can go from `origin` to `destination`, all relative to the relay chain.
+	///
+	/// Teleports are preferred, since they don't leave anything in a sovereign account.
+	/// Otherwise, one of the two chains has to be the reserve the other one trusts.
+	pub fn transfer_type(
+		&self,
+		origin: &Location,
+		destination: &Location,
+		asset: &Location,
+	) -> Result<TransferType, TransferError> {
+		if self.trusts(destination, asset, origin, Trust::Teleporter) {
+			Ok(TransferType::Teleport)
+		} else if self.trusts(destination, asset, origin, Trust::Reserve) {
+			Ok(TransferType::LocalReserve)
+		} else if self.trusts(origin, asset, destination, Trust::Reserve) {
+			Ok(TransferType::DestinationReserve)
+		} else {
+			Err(TransferError::Untrusted { asset: asset.clone() })
+		}
+	}
+}
+
+/// Why a transfer couldn't be built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransferError {
+	/// A parameter without a default wasn't given.
+	Missing(&'static str),
+	/// The location can't be expressed relative to the relay chain.
+	OutsideOfNetwork(Location),
+	/// Neither chain trusts the other one with this asset.
+	Untrusted { asset: Location },
+}
+
+/// Builds a `WithdrawAsset`, `PayFees`, `InitiateTransfer` program.
+///
+/// The assets are withdrawn, and so are the fees, unless they share an id with one of the
+/// assets. Then they're paid out of that asset and less of it arrives.
+/// Remote fees travel the way their asset does, and whatever arrives is deposited to the
+/// beneficiary after `remote_xcm`.
+#[derive(Clone, Debug)]
+pub struct TransferBuilder {
+	table: TrustTable,
+	origin: Location,
+	destination: Option<Location>,
+	assets: Vec<Asset>,
+	fee: Option<Asset>,
+	remote_fee: Option<Asset>,
+	beneficiary: Option<Location>,
+	preserve_origin: bool,
+	remote_xcm: Xcm<()>,
+}
+
+impl TransferBuilder {
+	/// A transfer from `origin`, using the trust of the Westend network.
+	pub fn from(origin: impl Into<Location>) -> Self {
+		Self {
+			table: TrustTable::westend(),
+			origin: origin.into(),
+			destination: None,
+			assets: Vec::new(),
+			fee: None,
+			remote_fee: None,
+			beneficiary: None,
+			preserve_origin: false,
+			remote_xcm: Xcm::new(),
+		}
+	}
+
+	pub fn trust_table(mut self, table: TrustTable) -> Self {
+		self.table = table;
+		self
+	}
+
+	pub fn to(mut self, destination: impl Into<Location>) -> Self {
+		self.destination = Some(destination.into());
+		self
+	}
+
+	/// Adds an asset to transfer.
+	pub fn asset(mut self, asset: impl Into<Asset>) -> Self {
+		self.assets.push(asset.into());
+		self
+	}
+
+	/// The fees for executing locally.
+	pub fn fee(mut self, fee: impl Into<Asset>) -> Self {
+		self.fee = Some(fee.into());
+		self
+	}
+
+	/// The fees for executing on the destination.
+	/// Without them, the destination has to take fees from what arrives some other way.
+	pub fn remote_fee(mut self, fee: impl Into<Asset>) -> Self {
+		self.remote_fee = Some(fee.into());
+		self
+	}
+
+	pub fn beneficiary(mut self, beneficiary: impl Into<Location>) -> Self {
+		self.beneficiary = Some(beneficiary.into());
+		self
+	}
+
+	/// Keeps the origin on the destination, needed for `Transact`.
+	pub fn preserve_origin(mut self, preserve_origin: bool) -> Self {
+		self.preserve_origin = preserve_origin;
+		self
+	}
+
+	/// Instructions executed on the destination, before the assets are deposited.
+	pub fn remote_xcm(mut self, xcm: Xcm<()>) -> Self {
+		self.remote_xcm = xcm;
+		self
+	}
+
+	/// The transfer type of `asset`, given as seen from the origin.
+	pub fn transfer_type_of(&self, asset: &Asset) -> Result<TransferType, TransferError> {
+		let destination = self.destination.as_ref().ok_or(TransferError::Missing("destination"))?;
+		let asset = self
+			.origin
+			.clone()
+			.appended_with(asset.id.0.clone())
+			.map_err(|(_, asset)| TransferError::OutsideOfNetwork(asset))?;
+		self.table.transfer_type(&self.origin, destination, &asset)
+	}
+
+	pub fn build<Call>(self) -> Result<Xcm<Call>, TransferError> {
+		let destination = self.destination.clone().ok_or(TransferError::Missing("destination"))?;
+		let fee = self.fee.clone().ok_or(TransferError::Missing("fee"))?;
+		let beneficiary = self.beneficiary.clone().ok_or(TransferError::Missing("beneficiary"))?;
+		let destination = destination
+			.clone()
+			.reanchored(&self.origin, &Here)
+			.map_err(|_| TransferError::OutsideOfNetwork(destination))?;
+
+		let remote_fees = self
+			.remote_fee
+			.as_ref()
+			.map(|fee| {
+				let transfer_type = self.transfer_type_of(fee)?;
+				Ok(filter(transfer_type, Definite(fee.clone().into())))
+			})
+			.transpose()?;
+		let transfer_types = self
+			.assets
+			.iter()
+			.map(|asset| self.transfer_type_of(asset))
+			.collect::<Result<Vec<_>, _>>()?;
+		// Assets with the same id are merged, so there's one per id left in holding.
+		let assets = Assets::from(self.assets.clone());
+		let count = assets.len() as u32;
+		// A single filter takes everything left, otherwise each asset needs its own.
+		let transfer_assets = match transfer_types.split_first() {
+			Some((first, rest)) if rest.iter().all(|other| other == first) =>
+				vec![filter(first.clone(), Wild(AllCounted(count)))],
+			_ => self
+				.assets
+				.iter()
+				.zip(transfer_types)
+				.map(|(asset, transfer_type)| {
+					let fun = match asset.fun {
+						Fungible(_) => WildFungible,
+						NonFungible(_) => WildNonFungible,
+					};
+					filter(transfer_type, Wild(AllOf { id: asset.id.clone(), fun }))
+				})
+				.collect(),
+		};
+
+		let mut to_withdraw = self.assets.clone();
+		for fee in [Some(fee.clone()), self.remote_fee.clone()].into_iter().flatten() {
+			if !to_withdraw.iter().any(|asset| asset.id == fee.id) {
+				to_withdraw.push(fee);
+			}
+		}
+		let mut remote_xcm = self.remote_xcm;
+		remote_xcm
+			.inner_mut()
+			.push(DepositAsset { assets: Wild(AllCounted(count)), beneficiary });
+
+		Ok(Xcm::<Call>::builder()
+			.withdraw_asset(Assets::from(to_withdraw))
+			.pay_fees(fee)
+			.initiate_transfer(
+				destination,
+				remote_fees,
+				self.preserve_origin,
+				transfer_assets,
+				remote_xcm,
+			)
+			.build())
+	}
+}
+
+pub(crate) fn filter(transfer_type: TransferType, assets: AssetFilter) -> AssetTransferFilter {
+	match transfer_type {
+		TransferType::Teleport => AssetTransferFilter::Teleport(assets),
+		TransferType::LocalReserve => AssetTransferFilter::ReserveDeposit(assets),
+		TransferType::DestinationReserve => AssetTransferFilter::ReserveWithdraw(assets),
+		// The table never picks a third chain as the reserve.
+		TransferType::RemoteReserve(_) => unreachable!("no remote reserves in the trust table"),
+	}
+}
@@ -0,0 +1,100 @@
+//! Ethereum style accounts.
+//!
+//! Accounts are the last 20 bytes of the keccak hash of an ECDSA public key, like on
+//! Ethereum. In XCM they're `AccountKey20` junctions instead of `AccountId32` ones.
+
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use core::fmt;
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_core::ecdsa;
+use sp_io::hashing::keccak_256;
+use sp_runtime::traits::{IdentifyAccount, Lazy, Verify};
+
+/// A 20 byte account.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	Hash,
+	Default,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	MaxEncodedLen,
+	TypeInfo,
+	Serialize,
+	Deserialize,
+)]
+pub struct AccountId20(pub [u8; 20]);
+
+impl fmt::Debug for AccountId20 {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "0x")?;
+		self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+	}
+}
+
+impl fmt::Display for AccountId20 {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(self, f)
+	}
+}
+
+impl From<[u8; 20]> for AccountId20 {
+	fn from(bytes: [u8; 20]) -> Self {
+		Self(bytes)
+	}
+}
+
+impl From<AccountId20> for [u8; 20] {
+	fn from(account: AccountId20) -> Self {
+		account.0
+	}
+}
+
+impl AccountId20 {
+	/// The account of a 32 byte hash, like the ones sovereign accounts are derived from.
+	///
+	/// Only the first 20 bytes are kept, so this is only meant for hashes: two 32 byte
+	/// accounts sharing them would end up as the same account.
+	pub fn from_hash(hash: [u8; 32]) -> Self {
+		let mut account = [0u8; 20];
+		account.copy_from_slice(&hash[..20]);
+		Self(account)
+	}
+}
+
+/// The signer of an `EthereumSignature`, identified by its address.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Encode, Decode, TypeInfo)]
+pub struct EthereumSigner(pub [u8; 20]);
+
+impl IdentifyAccount for EthereumSigner {
+	type AccountId = AccountId20;
+
+	fn into_account(self) -> AccountId20 {
+		AccountId20(self.0)
+	}
+}
+
+/// An ECDSA signature over the keccak hash of the message.
+#[derive(
+	Clone, PartialEq, Eq, Debug, Encode, Decode, DecodeWithMemTracking, MaxEncodedLen, TypeInfo,
+)]
+pub struct EthereumSignature(pub ecdsa::Signature);
+
+impl Verify for EthereumSignature {
+	type Signer = EthereumSigner;
+
+	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId20) -> bool {
+		let message_hash = keccak_256(msg.get());
+		let Ok(signature) = <&[u8; 65]>::try_from(self.0.as_ref()) else { return false };
+		match sp_io::crypto::secp256k1_ecdsa_recover(signature, &message_hash) {
+			Ok(public_key) => keccak_256(&public_key)[12..] == signer.0,
+			Err(_) => false,
+		}
+	}
+}
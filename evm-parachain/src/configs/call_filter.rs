@@ -0,0 +1,31 @@
+use crate::RuntimeCall;
+
+use frame_support::traits::Contains;
+
+/// Calls that are safe to dispatch from a `Transact` instruction.
+///
+/// The same ones the `parachain` runtime allows: remarks and transfers of the caller's own
+/// assets. Anything else, root calls included, fails with `XcmError::NoPermission`.
+pub struct SafeCallFilter;
+impl Contains<RuntimeCall> for SafeCallFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::System(
+				frame_system::Call::remark { .. } | frame_system::Call::remark_with_event { .. }
+			) | RuntimeCall::Balances(
+				pallet_balances::Call::transfer_allow_death { .. } |
+					pallet_balances::Call::transfer_keep_alive { .. } |
+					pallet_balances::Call::transfer_all { .. }
+			) | RuntimeCall::ForeignAssets(
+				pallet_assets::Call::transfer { .. } |
+					pallet_assets::Call::transfer_keep_alive { .. } |
+					pallet_assets::Call::approve_transfer { .. } |
+					pallet_assets::Call::cancel_approval { .. } |
+					pallet_assets::Call::transfer_approved { .. } |
+					pallet_assets::Call::touch { .. } |
+					pallet_assets::Call::refund { .. }
+			)
+		)
+	}
+}
@@ -0,0 +1,224 @@
+use super::call_filter::SafeCallFilter;
+use crate::{
+	AccountId, AllPalletsWithSystem, Balance, Balances, ForeignAssets, ParachainInfo,
+	ParachainSystem, PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, WeightToFee,
+	XcmpQueue,
+};
+use core::marker::PhantomData;
+use frame_support::{
+	parameter_types,
+	traits::{ConstU32, Everything, EverythingBut, Nothing},
+	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight, WeightToFee as _},
+};
+use frame_system::EnsureRoot;
+use pallet_xcm::XcmPassthrough;
+use parachains_common::TREASURY_PALLET_ID;
+use polkadot_runtime_common::impls::ToAuthor;
+use sp_runtime::traits::AccountIdConversion;
+use xcm::latest::prelude::*;
+use xcm_builder::{
+	AccountKey20Aliases, AllowTopLevelPaidExecutionFrom, DescribeAllTerminal, DescribeFamily,
+	DescribeLocation, EnsureXcmOrigin, FixedRateOfFungible, FixedWeightBounds,
+	FrameTransactionalProcessor, FungibleAdapter, FungiblesAdapter, HashedDescription, IsConcrete,
+	MatchedConvertedConcreteId, NoChecking, RelayChainAsNative, SiblingParachainAsNative,
+	SignedAccountKey20AsNative, SignedToAccountKey20, SovereignSignedViaLocation, StartsWith,
+	TakeWeightCredit, TrailingSetTopicAsId, UsingComponents, WithUniqueTopic,
+};
+use xcm_executor::{
+	traits::{ConvertLocation, JustTry, TakeRevenue, TransactAsset},
+	XcmExecutor,
+};
+use xcm_filters::{RelayAssetFrom, SiblingNativeAsset};
+
+pub const ASSET_HUB_ID: u32 = 1000;
+/// The para id of the `parachain` runtime, whose token we take for fees.
+pub const CUSTOM_PARA_ID: u32 = 2000;
+pub const EVM_PARA_ID: u32 = 2002;
+
+parameter_types! {
+	pub const RelayLocation: Location = Location::parent();
+	pub const HereLocation: Location = Location::here();
+	pub const RelayNetwork: Option<NetworkId> = None;
+	pub CheckingAccount: AccountId = PolkadotXcm::check_account();
+	pub RelayChainOrigin: RuntimeOrigin = cumulus_pallet_xcm::Origin::Relay.into();
+	pub UniversalLocation: InteriorLocation = Parachain(ParachainInfo::parachain_id().into()).into();
+	pub AssetHubLocation: Location = Location::new(1, [Parachain(ASSET_HUB_ID)]);
+	pub CustomParaLocation: Location = Location::new(1, [Parachain(CUSTOM_PARA_ID)]);
+}
+
+/// Converts a `Location` into an `AccountId`.
+///
+/// Local accounts are `AccountKey20` junctions, which map directly to our 20 byte accounts.
+/// Everything else gets an account from the hash of its description, truncated to 20 bytes.
+pub type LocationToAccountId = (
+	// Generates locations for all foreign locations via a sequence of hashes.
+	TruncatedHashedDescription<DescribeFamily<DescribeAllTerminal>>,
+	// Straight up local `AccountKey20` origins just alias directly to `AccountId`.
+	AccountKey20Aliases<RelayNetwork, AccountId>,
+);
+
+/// `HashedDescription`, with the hash truncated to one of our accounts.
+pub struct TruncatedHashedDescription<Describe>(PhantomData<Describe>);
+impl<Describe: DescribeLocation> ConvertLocation<AccountId>
+	for TruncatedHashedDescription<Describe>
+{
+	fn convert_location(location: &Location) -> Option<AccountId> {
+		HashedDescription::<[u8; 32], Describe>::convert_location(location)
+			.map(AccountId::from_hash)
+	}
+}
+
+/// Converts an (incoming) XCM origin into a local `Origin`, for `Transact`.
+pub type XcmOriginToTransactDispatchOrigin = (
+	SovereignSignedViaLocation<LocationToAccountId, RuntimeOrigin>,
+	RelayChainAsNative<RelayChainOrigin, RuntimeOrigin>,
+	SiblingParachainAsNative<cumulus_pallet_xcm::Origin, RuntimeOrigin>,
+	// An `AccountKey20` origin becomes a `Signed` origin of the same 20 byte account.
+	SignedAccountKey20AsNative<RelayNetwork, RuntimeOrigin>,
+	XcmPassthrough<RuntimeOrigin>,
+);
+
+/// Our native token.
+pub type LocalFungibleTransactor =
+	FungibleAdapter<Balances, IsConcrete<HereLocation>, LocationToAccountId, AccountId, ()>;
+
+/// Every other token, `WND` and `PARA` included.
+pub type ForeignFungiblesTransactor = FungiblesAdapter<
+	ForeignAssets,
+	MatchedConvertedConcreteId<
+		Location,
+		Balance,
+		EverythingBut<StartsWith<HereLocation>>,
+		JustTry,
+		JustTry,
+	>,
+	LocationToAccountId,
+	AccountId,
+	NoChecking,
+	CheckingAccount,
+>;
+
+pub type AssetTransactor = (LocalFungibleTransactor, ForeignFungiblesTransactor);
+
+pub type TrustedReserves = (RelayAssetFrom<AssetHubLocation>, SiblingNativeAsset);
+
+pub type Barrier = TrailingSetTopicAsId<(
+	TakeWeightCredit,
+	AllowTopLevelPaidExecutionFrom<Everything>,
+)>;
+
+parameter_types! {
+	// One XCM operation is 1_000_000_000 weight - almost certainly a conservative estimate.
+	pub UnitWeightCost: Weight = Weight::from_parts(1_000_000_000, 64 * 1024);
+	pub const MaxInstructions: u32 = 100;
+	pub const MaxAssetsIntoHolding: u32 = 64;
+}
+
+parameter_types! {
+	pub TreasuryAccount: AccountId = TREASURY_PALLET_ID.into_account_truncating();
+	pub FeePerSecond: u128 =
+		WeightToFee::weight_to_fee(&Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND, 0));
+	pub CustomParaFeePerSecond: (AssetId, u128, u128) =
+		(CustomParaLocation::get().into(), FeePerSecond::get(), 0);
+}
+
+/// Takes the fees paid in a foreign token and deposits them, still in that token, into
+/// [`TreasuryAccount`].
+///
+/// Our accounts are `AccountKey20` locations, which `XcmFeesTo32ByteAccount` can't build.
+pub struct ForeignFeesToTreasury;
+impl TakeRevenue for ForeignFeesToTreasury {
+	fn take_revenue(revenue: Asset) {
+		let treasury = AccountKey20 { network: None, key: TreasuryAccount::get().into() };
+		if let Err(error) = AssetTransactor::deposit_asset(&revenue, &treasury.into(), None) {
+			log::error!(target: "xcm::weight", "Failed to deposit {revenue:?}: {error:?}");
+		}
+	}
+}
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+	type RuntimeCall = RuntimeCall;
+	type XcmSender = XcmRouter;
+	type AssetTransactor = AssetTransactor;
+	type OriginConverter = XcmOriginToTransactDispatchOrigin;
+	type IsReserve = TrustedReserves;
+	// Nothing is teleported, there's no one we trust that much.
+	type IsTeleporter = ();
+	type UniversalLocation = UniversalLocation;
+	type Barrier = Barrier;
+	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+	type Trader = (
+		UsingComponents<WeightToFee, HereLocation, AccountId, Balances, ToAuthor<Runtime>>,
+		UsingComponents<WeightToFee, RelayLocation, AccountId, Balances, ToAuthor<Runtime>>,
+		// So `PARA` can be sent here without any other token.
+		// It's a foreign asset here, so `UsingComponents` would mint our token for the author.
+		FixedRateOfFungible<CustomParaFeePerSecond, ForeignFeesToTreasury>,
+	);
+	type ResponseHandler = PolkadotXcm;
+	type AssetTrap = PolkadotXcm;
+	type AssetClaims = PolkadotXcm;
+	type SubscriptionService = PolkadotXcm;
+	type PalletInstancesInfo = AllPalletsWithSystem;
+	type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+	type AssetLocker = ();
+	type AssetExchanger = ();
+	type FeeManager = ();
+	type MessageExporter = ();
+	type UniversalAliases = Nothing;
+	type CallDispatcher = RuntimeCall;
+	// Only calls that are safe to dispatch cross-chain can be used with `Transact`.
+	type SafeCallFilter = SafeCallFilter;
+	type Aliasers = Nothing;
+	type TransactionalProcessor = FrameTransactionalProcessor;
+	type HrmpNewChannelOpenRequestHandler = ();
+	type HrmpChannelAcceptedHandler = ();
+	type HrmpChannelClosingHandler = ();
+	type XcmRecorder = PolkadotXcm;
+	type XcmEventEmitter = PolkadotXcm;
+}
+
+/// Local accounts are `AccountKey20` junctions when sending or executing XCMs.
+pub type LocalOriginToLocation = SignedToAccountKey20<RuntimeOrigin, AccountId, RelayNetwork>;
+
+/// The means for routing XCM messages which are not for local execution into the right message
+/// queues.
+pub type XcmRouter = WithUniqueTopic<(
+	// Two routers - use UMP to communicate with the relay chain:
+	cumulus_primitives_utility::ParentAsUmp<ParachainSystem, (), ()>,
+	// ..and XCMP to communicate with the sibling chains.
+	XcmpQueue,
+)>;
+
+impl pallet_xcm::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+	type XcmRouter = XcmRouter;
+	type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+	// Local accounts can only execute programs that handle their own assets.
+	type XcmExecuteFilter = xcm_filters::XcmExecuteFilter<RuntimeCall>;
+	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmTeleportFilter = Nothing;
+	type XcmReserveTransferFilter = Everything;
+	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+	type UniversalLocation = UniversalLocation;
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+
+	const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
+	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+	type Currency = Balances;
+	type CurrencyMatcher = ();
+	type TrustedLockers = ();
+	type SovereignAccountOf = LocationToAccountId;
+	type MaxLockers = ConstU32<8>;
+	type WeightInfo = pallet_xcm::TestWeightInfo;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type MaxRemoteLockConsumers = ConstU32<0>;
+	type RemoteLockConsumerIdentifier = ();
+}
+
+impl cumulus_pallet_xcm::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type XcmExecutor = XcmExecutor<XcmConfig>;
+}
@@ -0,0 +1,130 @@
+//! Genesis presets for the EVM parachain.
+//!
+//! Same as the ones of the `parachain` runtime, but the well-known accounts are the 20 byte
+//! development accounts of Moonbeam instead of the `Sr25519Keyring` ones.
+
+use crate::{
+	account::AccountId20, configs::xcm_config::TreasuryAccount, AccountId, BalancesConfig,
+	CollatorSelectionConfig, ForeignAssetsConfig, ParachainInfoConfig, PolkadotXcmConfig,
+	RuntimeGenesisConfig, SessionConfig, SessionKeys, SudoConfig, EXISTENTIAL_DEPOSIT, UNITS,
+};
+
+use alloc::{vec, vec::Vec};
+use cumulus_primitives_core::ParaId;
+use frame_support::build_struct_json_patch;
+use hex_literal::hex;
+use serde_json::Value;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_genesis_builder::{PresetId, DEV_RUNTIME_PRESET};
+use sp_keyring::Sr25519Keyring;
+use xcm::latest::prelude::*;
+
+/// The preset used by the emulated network in the `emulator` crate.
+pub const EMULATOR_RUNTIME_PRESET: &str = "emulator";
+
+/// The para id every preset uses.
+pub const PARACHAIN_ID: u32 = crate::configs::xcm_config::EVM_PARA_ID;
+
+/// The amount of the native token every well-known account starts with.
+pub const ENDOWMENT: u128 = 1_000 * UNITS;
+
+pub const ALITH: [u8; 20] = hex!("f24FF3a9CF04c71Dbc94D0b566f7A27B94566cac");
+pub const BALTATHAR: [u8; 20] = hex!("3Cd0A705a2DC65e5b1E1205896BaA2be8A07c6e0");
+pub const CHARLETH: [u8; 20] = hex!("798d4Ba9baf0064Ec19eB4F0a1a45785ae9D6DFc");
+pub const DOROTHY: [u8; 20] = hex!("773539d4Ac0e786233D90A233654ccEE26a613D9");
+
+/// The XCM version we assume other chains speak until they tell us otherwise.
+const SAFE_XCM_VERSION: u32 = xcm::prelude::XCM_VERSION;
+
+/// Number of decimals of `WND`, the relay chain token.
+const WND_DECIMALS: u8 = 12;
+
+/// The development accounts.
+pub fn well_known_accounts() -> Vec<AccountId> {
+	[ALITH, BALTATHAR, CHARLETH, DOROTHY].into_iter().map(AccountId20).collect()
+}
+
+fn genesis(
+	invulnerables: Vec<(AccountId, AuraId)>,
+	endowed_accounts: Vec<AccountId>,
+	root: AccountId,
+	id: ParaId,
+) -> Value {
+	build_struct_json_patch!(RuntimeGenesisConfig {
+		balances: BalancesConfig {
+			balances: endowed_accounts
+				.iter()
+				.cloned()
+				.map(|k| (k, ENDOWMENT))
+				// Fees paid in foreign tokens go to the treasury, which has to exist to hold them.
+				.chain([(TreasuryAccount::get(), EXISTENTIAL_DEPOSIT)])
+				.collect::<Vec<_>>(),
+		},
+		parachain_info: ParachainInfoConfig { parachain_id: id },
+		collator_selection: CollatorSelectionConfig {
+			invulnerables: invulnerables.iter().cloned().map(|(acc, _)| acc).collect::<Vec<_>>(),
+			candidacy_bond: EXISTENTIAL_DEPOSIT * 16,
+		},
+		session: SessionConfig {
+			keys: invulnerables
+				.into_iter()
+				.map(|(acc, aura)| {
+					(
+						acc,                  // account id
+						acc,                  // validator id
+						SessionKeys { aura }, // session keys
+					)
+				})
+				.collect::<Vec<_>>(),
+		},
+		polkadot_xcm: PolkadotXcmConfig { safe_xcm_version: Some(SAFE_XCM_VERSION) },
+		// `WND` is registered from the start, owned by `root` so it can be minted in tests.
+		foreign_assets: ForeignAssetsConfig {
+			assets: vec![(Location::parent(), root, false, 1)],
+			metadata: vec![(Location::parent(), b"Westend".to_vec(), b"WND".to_vec(), WND_DECIMALS)],
+		},
+		sudo: SudoConfig { key: Some(root) },
+	})
+}
+
+/// Alith is the only collator. Aura keys are still sr25519, so it uses Alice's.
+fn development_genesis() -> Value {
+	genesis(
+		vec![(AccountId20(ALITH), Sr25519Keyring::Alice.public().into())],
+		well_known_accounts(),
+		AccountId20(ALITH),
+		PARACHAIN_ID.into(),
+	)
+}
+
+/// Alith and Baltathar as collators, with the aura keys of Alice and Bob.
+fn emulator_genesis() -> Value {
+	genesis(
+		vec![
+			(AccountId20(ALITH), Sr25519Keyring::Alice.public().into()),
+			(AccountId20(BALTATHAR), Sr25519Keyring::Bob.public().into()),
+		],
+		well_known_accounts(),
+		AccountId20(ALITH),
+		PARACHAIN_ID.into(),
+	)
+}
+
+/// Provides the JSON representation of predefined genesis config for given `id`.
+pub fn get_preset(id: &PresetId) -> Option<Vec<u8>> {
+	let patch = match id.as_ref() {
+		DEV_RUNTIME_PRESET => development_genesis(),
+		EMULATOR_RUNTIME_PRESET => emulator_genesis(),
+		_ => return None,
+	};
+	Some(
+		serde_json::to_string(&patch)
+			.expect("serialization to json is expected to work. qed.")
+			.into_bytes(),
+	)
+}
+
+/// List of supported presets.
+pub fn preset_names() -> Vec<PresetId> {
+	vec![PresetId::from(DEV_RUNTIME_PRESET), PresetId::from(EMULATOR_RUNTIME_PRESET)]
+}
@@ -3,6 +3,7 @@
 use codec::Encode;
 use emulator::prelude::*;
 use frame_support::assert_ok;
+use parachain_runtime::pallets::xcm_failures;
 use xcm::prelude::*;
 
 #[test]
@@ -36,6 +37,15 @@ fn require_fee_payment() {
         assert_expected_events!(
             CustomPara,
             vec![
+                // We know exactly why it failed.
+                RuntimeEvent::XcmFailures(xcm_failures::Event::ExecutionFailed {
+                    origin,
+                    error,
+                    ..
+                }) => {
+                    origin: *origin == Location::new(1, [Parachain(AssetHubWestend::para_id().into())]),
+                    error: *error == XcmError::Barrier,
+                },
                 RuntimeEvent::MessageQueue(pallet_message_queue::Event::Processed {
                     success: false,
                     ..
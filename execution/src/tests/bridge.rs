@@ -0,0 +1,224 @@
+//! Tests for transfers between `WestendNetwork` and `RococoNetwork`.
+//!
+//! Both networks are bridged by their bridge hubs. Asset hubs don't talk to the bridge
+//! directly: their router wraps messages for another consensus in an `ExportMessage`
+//! and sends it to the local bridge hub, which relays it to the other side.
+
+use super::common;
+
+use emulator::prelude::*;
+use frame_support::{
+	assert_ok,
+	traits::tokens::{fungible, fungibles},
+	weights::Weight,
+};
+use xcm::{
+	latest::AssetTransferFilter,
+	opaque::latest::{ROCOCO_GENESIS_HASH, WESTEND_GENESIS_HASH},
+	prelude::*,
+};
+
+/// Prepares `RococoNetwork` to receive `WND`.
+///
+/// `WND` is registered on `AssetHubRococo` and a pool with `ROC` is created, so it can be
+/// used to pay for fees. `AssetHubWestend` also needs funds on its bridge hub, to pay for
+/// exporting messages.
+fn setup_bridge() -> Location {
+	let ahr_sender = AssetHubRococoSender::get();
+	// `WND` seen from `AssetHubRococo`: the native token of another consensus.
+	let bridged_wnd = Location::new(2, [GlobalConsensus(ByGenesis(WESTEND_GENESIS_HASH))]);
+	let asset_hub_westend = Location::new(1, [Parachain(AssetHubWestend::para_id().into())]);
+
+	NetworkGenesis::new()
+		.bridge_hub(ChainGenesis::new().sovereign_balance(asset_hub_westend, 10 * WND_UNITS))
+		.asset_hub_rococo(
+			ChainGenesis::new()
+				.foreign_asset(bridged_wnd.clone(), ahr_sender.clone(), 1)
+				.balance(ahr_sender.clone(), 1000 * ROC_UNITS)
+				.foreign_balance(bridged_wnd.clone(), ahr_sender.clone(), 200 * WND_UNITS)
+				// Both tokens are worth the same.
+				.pool(
+					(Location::parent(), 100 * ROC_UNITS),
+					(bridged_wnd.clone(), 100 * WND_UNITS),
+					ahr_sender,
+				),
+		)
+		.apply();
+
+	bridged_wnd
+}
+
+/// The other asset hub, seen from the chains of `WestendNetwork`.
+fn asset_hub_rococo_from_westend() -> Location {
+	let para_id: u32 = AssetHubRococo::para_id().into();
+	Location::new(2, [GlobalConsensus(ByGenesis(ROCOCO_GENESIS_HASH)), Parachain(para_id)])
+}
+
+// Scenario:
+// A sender on `CustomPara` wants to send some `WND` to an account on `AssetHubRococo`.
+// `AssetHubWestend` is the reserve of `WND`, so the assets go through it.
+// From there, they're reserve transferred over the bridge.
+#[test]
+fn transfer_wnd_to_asset_hub_rococo() {
+	let initial_wnd_balance = 10 * WND_UNITS;
+	let initial_para_balance = 10 * PARA_UNITS;
+	let (sender, _) = common::setup(initial_wnd_balance, initial_para_balance);
+	let bridged_wnd = setup_bridge();
+	let receiver = AssetHubRococoReceiver::get();
+	let transfer_amount = 5 * WND_UNITS;
+	let fees_amount = 10 * PARA_CENTS;
+	// Pays for the execution on `AssetHubWestend` and for exporting the message.
+	let asset_hub_westend_fees_amount = 1 * WND_UNITS;
+	// Swapped to `ROC` on `AssetHubRococo`.
+	let asset_hub_rococo_fees_amount = 10 * WND_CENTS;
+
+	let asset_hub_westend = Location::new(1, [Parachain(AssetHubWestend::para_id().into())]);
+	let asset_hub_rococo = asset_hub_rococo_from_westend();
+
+	// Second hop, executed on `AssetHubWestend`.
+	// It holds the `WND`, so it deposits it in the sovereign account of `AssetHubRococo`.
+	let xcm_on_asset_hub_westend = Xcm::<()>::builder_unsafe()
+		.initiate_transfer(
+			asset_hub_rococo.clone(),
+			AssetTransferFilter::ReserveDeposit(
+				Definite((Parent, asset_hub_rococo_fees_amount).into()),
+			),
+			false,
+			vec![AssetTransferFilter::ReserveDeposit(Wild(AllCounted(1)))],
+			Xcm::<()>::builder_unsafe().deposit_asset(AllCounted(1), receiver.clone()).build(),
+		)
+		.build();
+	// First hop, executed locally.
+	let assets_to_withdraw: Assets =
+		vec![(Here, fees_amount).into(), (Parent, transfer_amount).into()].into();
+	let xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder()
+		.withdraw_asset(assets_to_withdraw)
+		.pay_fees((Here, fees_amount))
+		.initiate_transfer(
+			asset_hub_westend,
+			AssetTransferFilter::ReserveWithdraw(
+				Definite((Parent, asset_hub_westend_fees_amount).into()),
+			),
+			false,
+			vec![AssetTransferFilter::ReserveWithdraw(Wild(AllCounted(1)))],
+			xcm_on_asset_hub_westend,
+		)
+		.build();
+
+	CustomPara::execute_with(|| {
+		type ForeignAssets = <CustomPara as CustomParaPallet>::ForeignAssets;
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender.clone()),
+			Box::new(VersionedXcm::from(xcm)),
+			Weight::MAX,
+		));
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::balance(Location::parent(), &sender),
+			initial_wnd_balance - transfer_amount
+		);
+	});
+	// Messages go from `CustomPara` to `AssetHubWestend`, its bridge hub, the other bridge
+	// hub and finally `AssetHubRococo`.
+	drain_queues(10).unwrap();
+
+	// The asset hub didn't send the message over the bridge itself, it asked the bridge hub
+	// to export it.
+	let exported = MessageLog::get()
+		.from(Parachain(AssetHubWestend::para_id().into()))
+		.to(Parachain(BridgeHubWestend::para_id().into()))
+		.containing(|instruction| matches!(instruction, ExportMessage { .. }));
+	assert_eq!(exported.len(), 1, "{}", MessageLog::get().pretty());
+
+	// What was reserve deposited is backed by `WND` in the sovereign account of
+	// `AssetHubRococo`.
+	AssetHubWestend::execute_with(|| {
+		type Balances = <AssetHubWestend as AssetHubWestendPallet>::Balances;
+		let asset_hub_rococo_sovereign_account =
+			AssetHubWestend::sovereign_account_id_of(asset_hub_rococo);
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&asset_hub_rococo_sovereign_account),
+			transfer_amount - asset_hub_westend_fees_amount
+		);
+	});
+
+	AssetHubRococo::execute_with(|| {
+		type ForeignAssets = <AssetHubRococo as AssetHubRococoPallet>::ForeignAssets;
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::balance(bridged_wnd, &receiver),
+			transfer_amount - asset_hub_westend_fees_amount - asset_hub_rococo_fees_amount
+		);
+	});
+}
+
+// Scenario:
+// The bridged message arrives on `AssetHubRococo` from its own bridge hub, but it still needs
+// to know who sent it. The bridge hub prefixes it with the origin on the other side.
+#[test]
+fn bridged_message_keeps_its_origin() {
+	let (sender, _) = common::setup(10 * WND_UNITS, 10 * PARA_UNITS);
+	setup_bridge();
+	let receiver = AssetHubRococoReceiver::get();
+	let asset_hub_rococo = asset_hub_rococo_from_westend();
+
+	let xcm_on_asset_hub_westend = Xcm::<()>::builder_unsafe()
+		.initiate_transfer(
+			asset_hub_rococo,
+			AssetTransferFilter::ReserveDeposit(Definite((Parent, 10 * WND_CENTS).into())),
+			false,
+			vec![AssetTransferFilter::ReserveDeposit(Wild(AllCounted(1)))],
+			Xcm::<()>::builder_unsafe().deposit_asset(AllCounted(1), receiver).build(),
+		)
+		.build();
+	let assets_to_withdraw: Assets =
+		vec![(Here, 10 * PARA_CENTS).into(), (Parent, 2 * WND_UNITS).into()].into();
+	let xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder()
+		.withdraw_asset(assets_to_withdraw)
+		.pay_fees((Here, 10 * PARA_CENTS))
+		.initiate_transfer(
+			Location::new(1, [Parachain(AssetHubWestend::para_id().into())]),
+			AssetTransferFilter::ReserveWithdraw(Definite((Parent, 1 * WND_UNITS).into())),
+			false,
+			vec![AssetTransferFilter::ReserveWithdraw(Wild(AllCounted(1)))],
+			xcm_on_asset_hub_westend,
+		)
+		.build();
+
+	CustomPara::execute_with(|| {
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender),
+			Box::new(VersionedXcm::from(xcm)),
+			Weight::MAX,
+		));
+	});
+	drain_queues(10).unwrap();
+
+	// Chains of `RococoNetwork` are captured with their consensus as prefix.
+	let rococo = GlobalConsensus(ByGenesis(ROCOCO_GENESIS_HASH));
+	let delivered = MessageLog::get()
+		.from(Location::new(0, [rococo, Parachain(BridgeHubRococo::para_id().into())]))
+		.to(Location::new(0, [rococo, Parachain(AssetHubRococo::para_id().into())]));
+	assert_eq!(delivered.len(), 1, "{}", MessageLog::get().pretty());
+	let instructions = delivered[0].instructions();
+	assert_eq!(
+		instructions[..2],
+		[
+			UniversalOrigin(GlobalConsensus(ByGenesis(WESTEND_GENESIS_HASH))),
+			DescendOrigin(Parachain(AssetHubWestend::para_id().into()).into()),
+		]
+	);
+	// And the whole journey succeeded.
+	let topic = delivered[0].topic.expect("messages are sent with a topic");
+	assert!(Timeline::follow(topic).is_success(), "{}", Timeline::follow(topic));
+}
+
+// Scenario:
+// Nothing is in flight, so draining the queues builds no block, not even on the bridge hubs.
+#[test]
+fn draining_an_idle_network_builds_no_blocks() {
+	// Whatever genesis left in the queues is delivered first.
+	drain_queues(10).unwrap();
+	let block_numbers = common::block_numbers();
+
+	assert_eq!(drain_queues(10), Ok(0));
+	assert_eq!(common::block_numbers(), block_numbers);
+}
@@ -0,0 +1,89 @@
+//! Tests for configuring the `SafeCallFilter`.
+
+use codec::Encode;
+use emulator::prelude::*;
+use frame_support::{assert_err_ignore_postinfo, assert_ok};
+use xcm::prelude::*;
+
+type Runtime = <CustomPara as Chain>::Runtime;
+type RuntimeCall = <CustomPara as Chain>::RuntimeCall;
+
+#[test]
+fn transact_safe_call_works() {
+	let sender = CustomParaSender::get();
+	let remark = b"Hello from XCM!".to_vec();
+	let remark_hash = sp_io::hashing::blake2_256(&remark);
+	let call = RuntimeCall::System(frame_system::Call::<Runtime>::remark_with_event { remark })
+		.encode();
+	let xcm = Xcm::<RuntimeCall>::builder_unsafe()
+		.transact(OriginKind::SovereignAccount, None, call)
+		.build();
+
+	CustomPara::execute_with(|| {
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender.clone()),
+			Box::new(VersionedXcm::from(xcm)),
+			Weight::MAX,
+		));
+
+		<CustomPara as CustomParaPallet>::System::assert_has_event(
+			frame_system::Event::Remarked { sender, hash: remark_hash.into() }.into(),
+		);
+	});
+}
+
+#[test]
+fn transact_sudo_call_is_filtered() {
+	let sender = CustomParaSender::get();
+	// The sender is the sudo key, but this call should never come in through XCM.
+	let call = RuntimeCall::Sudo(pallet_sudo::Call::<Runtime>::sudo {
+		call: Box::new(RuntimeCall::System(frame_system::Call::<Runtime>::remark_with_event {
+			remark: b"I am root now".to_vec(),
+		})),
+	})
+	.encode();
+	let xcm = Xcm::<RuntimeCall>::builder_unsafe()
+		.transact(OriginKind::SovereignAccount, None, call)
+		.build();
+
+	CustomPara::execute_with(|| {
+		assert_err_ignore_postinfo!(
+			<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+				<CustomPara as Chain>::RuntimeOrigin::signed(sender),
+				Box::new(VersionedXcm::from(xcm)),
+				Weight::MAX,
+			),
+			pallet_xcm::Error::<Runtime>::LocalExecutionIncompleteWithError {
+				index: 0,
+				error: pallet_xcm::ExecutionError::NoPermission,
+			}
+		);
+	});
+}
+
+#[test]
+fn transact_root_call_is_filtered() {
+	let sender = CustomParaSender::get();
+	// Only root can change the default version, no message should be able to.
+	let call = RuntimeCall::PolkadotXcm(pallet_xcm::Call::<Runtime>::force_default_xcm_version {
+		maybe_xcm_version: Some(3),
+	})
+	.encode();
+	let xcm = Xcm::<RuntimeCall>::builder_unsafe()
+		.transact(OriginKind::Superuser, None, call)
+		.build();
+
+	CustomPara::execute_with(|| {
+		assert_err_ignore_postinfo!(
+			<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+				<CustomPara as Chain>::RuntimeOrigin::signed(sender),
+				Box::new(VersionedXcm::from(xcm)),
+				Weight::MAX,
+			),
+			pallet_xcm::Error::<Runtime>::LocalExecutionIncompleteWithError {
+				index: 0,
+				error: pallet_xcm::ExecutionError::NoPermission,
+			}
+		);
+	});
+}
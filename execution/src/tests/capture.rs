@@ -0,0 +1,52 @@
+//! Tests for the messages captured by `execute_and_capture`.
+
+use codec::Encode;
+use emulator::prelude::*;
+use frame_support::assert_ok;
+use serde_json::json;
+use xcm::prelude::*;
+
+/// `bytes` as a `0x` prefixed hex string.
+fn hex(bytes: &[u8]) -> String {
+	format!("0x{}", bytes.iter().map(|byte| format!("{byte:02x}")).collect::<String>())
+}
+
+// A captured message is exported as JSON with where it went, how and what it said.
+#[test]
+fn captured_messages_are_exported_as_json() {
+	let sender = CustomParaSender::get();
+	let topic = [7; 32];
+	let message = Xcm::<()>::builder_unsafe().clear_origin().set_topic(topic).build();
+
+	let block = CustomPara::execute_and_capture(|| {
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::send(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender.clone()),
+			Box::new(VersionedLocation::from(Location::parent())),
+			Box::new(VersionedXcm::from(message)),
+		));
+		frame_system::Pallet::<<CustomPara as Chain>::Runtime>::block_number()
+	});
+
+	// The sender's account is put first, since it's not the chain itself sending.
+	let sent: Xcm<()> = vec![
+		DescendOrigin([AccountId32 { network: None, id: sender.into() }].into()),
+		ClearOrigin,
+		SetTopic(topic),
+	]
+	.into();
+	let instructions: Vec<_> =
+		sent.inner().iter().map(|instruction| format!("{instruction:?}")).collect();
+	let expected = json!({
+		"source": "para 2000",
+		"destination": "relay",
+		"block": block,
+		"transport": "Ump",
+		"topic": hex(&topic),
+		"instructions": instructions,
+		"encoded": hex(&VersionedXcm::from(sent).encode()),
+	});
+	let log = MessageLog::get().from(Parachain(CustomPara::para_id().into()));
+	assert_eq!(log.len(), 1, "{}", log.pretty());
+	assert_eq!(log[0].to_json(), expected);
+	assert_eq!(log.to_json(), json!([expected]));
+}
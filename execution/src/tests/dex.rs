@@ -0,0 +1,157 @@
+//! Tests for routes that swap on `DexPara`, a parachain with asset pools.
+//!
+//! Swaps happen in the middle of a program with `ExchangeAsset`, so assets can change on their
+//! way to the destination without any extra transaction.
+
+use super::common;
+
+use emulator::prelude::*;
+use frame_support::{
+	assert_ok,
+	traits::tokens::{fungible, fungibles},
+	weights::Weight,
+};
+use xcm::{latest::AssetTransferFilter, prelude::*};
+
+/// Creates a pool between `WND` and `PARA` on `DexPara`.
+///
+/// `DexPara` holds its `WND` on `AssetHubWestend`, so its sovereign account there is funded
+/// with what's put in the pool.
+fn setup_dex() -> Location {
+	let dex_sender = DexParaSender::get();
+	let custom_para_from_dex = DexPara::sibling_location_of(CustomPara::para_id());
+	let dex_from_asset_hub = AssetHubWestend::sibling_location_of(DexPara::para_id());
+
+	NetworkGenesis::new()
+		.asset_hub(ChainGenesis::new().sovereign_balance(dex_from_asset_hub, 100 * WND_UNITS))
+		.dex_para(
+			ChainGenesis::new()
+				// `WND` is already registered by the genesis preset.
+				.foreign_asset(custom_para_from_dex.clone(), dex_sender.clone(), 1)
+				.foreign_balance(Location::parent(), dex_sender.clone(), 100 * WND_UNITS)
+				.foreign_balance(custom_para_from_dex.clone(), dex_sender.clone(), 200 * PARA_UNITS)
+				// Same price as on the asset hub, `PARA` is worth half of `WND`.
+				.pool(
+					(Location::parent(), 100 * WND_UNITS),
+					(custom_para_from_dex.clone(), 200 * PARA_UNITS),
+					dex_sender,
+				),
+		)
+		.apply();
+
+	custom_para_from_dex
+}
+
+// Scenario:
+// A sender on `CustomPara` has `PARA` but wants to send `WND` to someone on the asset hub.
+// A single program takes the `PARA` to `DexPara`, swaps it for `WND` there and sends that to
+// the asset hub.
+// Each hop pays for its execution with what it has: `PARA` up to the swap, `WND` after it.
+#[test]
+fn swap_on_dex_and_deliver_to_asset_hub() {
+	let initial_para_balance = 10 * PARA_UNITS;
+	let (sender, receiver) = common::setup(10 * WND_UNITS, initial_para_balance);
+	let custom_para_from_dex = setup_dex();
+	let transfer_amount = 1 * PARA_UNITS;
+	// Paid in `PARA` on `CustomPara`.
+	let fees_amount = 10 * PARA_CENTS;
+	// Paid in `PARA` on `DexPara`, before the swap.
+	let dex_fees_amount = 10 * PARA_CENTS;
+	// Paid in `WND` on `AssetHubWestend`, after the swap.
+	let asset_hub_fees_amount = 10 * WND_CENTS;
+	let swapped_amount = transfer_amount - fees_amount - dex_fees_amount;
+
+	// What we'll get for our `PARA`, as the pool is now.
+	let expected_wnd = DexPara::execute_with(|| {
+		<DexPara as DexParaPallet>::AssetConversion::quote_price_exact_tokens_for_tokens(
+			custom_para_from_dex.clone(),
+			Location::parent(),
+			swapped_amount,
+			true,
+		)
+		.expect("the pool exists")
+	});
+
+	let dex = Location::new(1, [Parachain(DexPara::para_id().into())]);
+	let asset_hub_from_dex = Location::new(1, [Parachain(AssetHubWestend::para_id().into())]);
+	// Last hop, executed on `AssetHubWestend`.
+	let xcm_on_asset_hub =
+		Xcm::<()>::builder_unsafe().deposit_asset(AllCounted(1), receiver.clone()).build();
+	// Second hop, executed on `DexPara`.
+	// Locations are the ones seen from there, so `PARA` is its sibling's token.
+	let xcm_on_dex = Xcm::<()>::builder_unsafe()
+		.exchange_asset(
+			Wild(AllOf { id: AssetId(custom_para_from_dex.clone()), fun: WildFungible }),
+			(Parent, 1u128),
+			// Everything is swapped, we only set the minimum we accept.
+			true,
+		)
+		.initiate_transfer(
+			asset_hub_from_dex,
+			AssetTransferFilter::ReserveWithdraw(Definite((Parent, asset_hub_fees_amount).into())),
+			false,
+			vec![AssetTransferFilter::ReserveWithdraw(Wild(AllCounted(1)))],
+			xcm_on_asset_hub,
+		)
+		.build();
+	// First hop, executed locally.
+	let xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder()
+		.withdraw_asset((Here, transfer_amount))
+		.pay_fees((Here, fees_amount))
+		.initiate_transfer(
+			dex,
+			AssetTransferFilter::ReserveDeposit(Definite((Here, dex_fees_amount).into())),
+			false,
+			vec![AssetTransferFilter::ReserveDeposit(Wild(AllCounted(1)))],
+			xcm_on_dex,
+		)
+		.build();
+
+	let receiver_balance_before = AssetHubWestend::execute_with(|| {
+		type Balances = <AssetHubWestend as AssetHubWestendPallet>::Balances;
+		<Balances as fungible::Inspect<_>>::balance(&receiver)
+	});
+
+	CustomPara::execute_with(|| {
+		type Balances = <CustomPara as CustomParaPallet>::Balances;
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender.clone()),
+			Box::new(VersionedXcm::from(xcm)),
+			Weight::MAX,
+		));
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&sender),
+			initial_para_balance - transfer_amount
+		);
+	});
+	drain_queues(5).unwrap();
+
+	// The swap happened on `DexPara`, which sent `WND` on to the asset hub.
+	let from_dex = MessageLog::get()
+		.from(Parachain(DexPara::para_id().into()))
+		.to(Parachain(AssetHubWestend::para_id().into()));
+	assert_eq!(from_dex.len(), 1, "{}", MessageLog::get().pretty());
+
+	// The pool got all the `PARA` that was left.
+	DexPara::execute_with(|| {
+		type ForeignAssets = <DexPara as DexParaPallet>::ForeignAssets;
+		type AssetConversion = <DexPara as DexParaPallet>::AssetConversion;
+		let (wnd_reserve, para_reserve) =
+			AssetConversion::get_reserves(Location::parent(), custom_para_from_dex)
+				.expect("the pool exists");
+		assert_eq!(wnd_reserve, 100 * WND_UNITS - expected_wnd);
+		assert_eq!(para_reserve, 200 * PARA_UNITS + swapped_amount);
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::total_issuance(Location::parent()),
+			100 * WND_UNITS - expected_wnd
+		);
+	});
+
+	AssetHubWestend::execute_with(|| {
+		type Balances = <AssetHubWestend as AssetHubWestendPallet>::Balances;
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&receiver),
+			receiver_balance_before + expected_wnd - asset_hub_fees_amount
+		);
+	});
+}
@@ -0,0 +1,189 @@
+//! Tests for transfers between `CustomPara` and `EvmPara`, a chain with 20 byte accounts.
+//!
+//! Accounts on `EvmPara` are `AccountKey20` junctions instead of `AccountId32` ones.
+//! Our runtime maps those to local accounts by hashing them, so they can receive assets too.
+
+use super::common;
+
+use emulator::prelude::*;
+use frame_support::{
+	assert_ok,
+	traits::tokens::{
+		fungible,
+		fungibles::{self, Create},
+	},
+	weights::Weight,
+};
+use xcm::prelude::*;
+
+/// Registers `PARA` from `CustomPara` as a foreign asset on `EvmPara`.
+fn register_custom_para_token_on_evm_para() -> Location {
+	let custom_para_from_evm_para = EvmPara::sibling_location_of(CustomPara::para_id());
+	EvmPara::execute_with(|| {
+		type ForeignAssets = <EvmPara as EvmParaPallet>::ForeignAssets;
+		assert_ok!(<ForeignAssets as Create<_>>::create(
+			custom_para_from_evm_para.clone(),
+			AccountId20(ALITH),
+			false,
+			1,
+		));
+	});
+	custom_para_from_evm_para
+}
+
+/// An account on a chain with 20 byte accounts.
+fn account_key20(key: [u8; 20]) -> Location {
+	AccountKey20 { network: None, key }.into()
+}
+
+// Scenario:
+// A sender on `CustomPara` wants to send some `PARA` to Alith, on `EvmPara`.
+// It's the same as sending it to a sibling, only the beneficiary looks different.
+#[test]
+fn reserve_transfer_to_account_key20() {
+	let initial_para_balance = 10 * PARA_UNITS;
+	let (sender, _) = common::setup(10 * WND_UNITS, initial_para_balance);
+	let custom_para_from_evm_para = register_custom_para_token_on_evm_para();
+	let transfer_amount = 1 * PARA_UNITS;
+	let fees_amount = 10 * PARA_CENTS;
+	let remote_fees_amount = 10 * PARA_CENTS;
+
+	// `EvmPara` takes our token for fees.
+	let xcm = TransferBuilder::from(Parachain(CustomPara::para_id().into()))
+		.to(Parachain(EvmPara::para_id().into()))
+		.asset((Here, transfer_amount))
+		.fee((Here, fees_amount))
+		.remote_fee((Here, remote_fees_amount))
+		.beneficiary(account_key20(ALITH))
+		.build::<<CustomPara as Chain>::RuntimeCall>()
+		.expect("`EvmPara` trusts us with our token");
+
+	CustomPara::execute_with(|| {
+		type Balances = <CustomPara as CustomParaPallet>::Balances;
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender.clone()),
+			Box::new(VersionedXcm::from(xcm)),
+			Weight::MAX,
+		));
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&sender),
+			initial_para_balance - transfer_amount
+		);
+	});
+	drain_queues(5).unwrap();
+
+	// `AccountKey20Aliases` turns the junction into Alith's account.
+	EvmPara::execute_with(|| {
+		type ForeignAssets = <EvmPara as EvmParaPallet>::ForeignAssets;
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::balance(
+				custom_para_from_evm_para,
+				&AccountId20(ALITH)
+			),
+			transfer_amount - fees_amount - remote_fees_amount
+		);
+	});
+}
+
+// Scenario:
+// The same transfer, planned instead of assembled by hand.
+// `EvmPara` is asked what it charges in `PARA`, like any other chain on a route.
+#[test]
+fn planned_transfer_to_account_key20() {
+	let (sender, _) = common::setup(10 * WND_UNITS, 10 * PARA_UNITS);
+	let custom_para_from_evm_para = register_custom_para_token_on_evm_para();
+	let transfer_amount = 1 * PARA_UNITS;
+
+	let custom_para = Parachain(CustomPara::para_id().into());
+	let route = RoutePlanner::from(custom_para)
+		.asset((custom_para, transfer_amount))
+		.fee_asset(custom_para)
+		.hop(Parachain(EvmPara::para_id().into()), custom_para)
+		.beneficiary(account_key20(ALITH))
+		.plan::<<CustomPara as Chain>::RuntimeCall>()
+		.expect("`EvmPara` can size its fees");
+
+	CustomPara::execute_with(|| {
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender),
+			Box::new(VersionedXcm::from(route.xcm.clone())),
+			Weight::MAX,
+		));
+	});
+	drain_queues(5).unwrap();
+
+	EvmPara::execute_with(|| {
+		type ForeignAssets = <EvmPara as EvmParaPallet>::ForeignAssets;
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::balance(
+				custom_para_from_evm_para,
+				&AccountId20(ALITH)
+			),
+			transfer_amount - route.fees_in(custom_para)
+		);
+	});
+}
+
+// Scenario:
+// Alith sends `PARA` from `EvmPara` back to `CustomPara`, to the same key.
+// There's no 20 byte account on `CustomPara`, so the key is hashed into a 32 byte one.
+#[test]
+fn reserve_withdraw_to_account_key20() {
+	let initial_para_balance = 10 * PARA_UNITS;
+	let evm_para = Location::new(1, [Parachain(EvmPara::para_id().into())]);
+	// Everything `EvmPara` holds is backed by its sovereign account on `CustomPara`.
+	NetworkGenesis::new()
+		.custom_para(ChainGenesis::new().sovereign_balance(evm_para, initial_para_balance))
+		.apply();
+	let custom_para_from_evm_para = register_custom_para_token_on_evm_para();
+	EvmPara::execute_with(|| {
+		type ForeignAssets = <EvmPara as EvmParaPallet>::ForeignAssets;
+		<ForeignAssets as fungibles::Mutate<_>>::set_balance(
+			custom_para_from_evm_para.clone(),
+			&AccountId20(ALITH),
+			initial_para_balance,
+		);
+	});
+	let transfer_amount = 1 * PARA_UNITS;
+	let fees_amount = 10 * PARA_CENTS;
+	let remote_fees_amount = 10 * PARA_CENTS;
+
+	// `CustomPara` is the reserve, so the assets are withdrawn from our sovereign account there.
+	let xcm = TransferBuilder::from(Parachain(EvmPara::para_id().into()))
+		.to(Parachain(CustomPara::para_id().into()))
+		.asset((custom_para_from_evm_para.clone(), transfer_amount))
+		.fee((custom_para_from_evm_para.clone(), fees_amount))
+		.remote_fee((custom_para_from_evm_para.clone(), remote_fees_amount))
+		.beneficiary(account_key20(ALITH))
+		.build::<<EvmPara as Chain>::RuntimeCall>()
+		.expect("we trust `CustomPara` as the reserve of its token");
+
+	EvmPara::execute_with(|| {
+		type ForeignAssets = <EvmPara as EvmParaPallet>::ForeignAssets;
+		// Signed origins are `AccountKey20` locations on this chain.
+		assert_ok!(<EvmPara as EvmParaPallet>::PolkadotXcm::execute(
+			<EvmPara as Chain>::RuntimeOrigin::signed(AccountId20(ALITH)),
+			Box::new(VersionedXcm::from(xcm)),
+			Weight::MAX,
+		));
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::balance(
+				custom_para_from_evm_para,
+				&AccountId20(ALITH)
+			),
+			initial_para_balance - transfer_amount
+		);
+	});
+	drain_queues(5).unwrap();
+
+	// `LocationToAccountId` gives the account the key maps to.
+	// It's the same one every time, so the funds can always be found again.
+	let receiver = CustomPara::sovereign_account_id_of(account_key20(ALITH));
+	CustomPara::execute_with(|| {
+		type Balances = <CustomPara as CustomParaPallet>::Balances;
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&receiver),
+			transfer_amount - fees_amount - remote_fees_amount
+		);
+	});
+}
@@ -1,5 +1,7 @@
-//! The prelude will bring in these three emulated chains:
-//! - Westend, AssetHubWestend, CustomPara
+//! The prelude will bring in these emulated chains:
+//! - Westend, AssetHubWestend, BridgeHubWestend, CustomPara, SiblingPara, EvmPara,
+//!   DexPara
+//! - Rococo, AssetHubRococo, BridgeHubRococo, bridged to the ones above
 //!
 //! They all come with accounts called [..]Sender and [..]Receiver,
 //! i.e AssetHubWestendSender.
@@ -66,7 +68,7 @@ fn cross_chain_transfer() {
 		Xcm::<()>::builder_unsafe().deposit_asset(AllCounted(1), receiver.clone()).build();
 
 	// We assemble everything into the XCM we'll execute locally.
-	let xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder()
+	let expected = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder()
 		.withdraw_asset(assets_to_withdraw)
 		.pay_fees(fees_assets)
 		.initiate_transfer(
@@ -78,6 +80,21 @@ fn cross_chain_transfer() {
 		)
 		.build();
 
+	// `TransferBuilder` picks the transfer types from what each chain trusts, and gives us
+	// the same program. It's how we build programs in the rest of these tests.
+	let xcm = TransferBuilder::from(Parachain(CustomPara::para_id().into()))
+		.to(Parachain(AssetHubWestend::para_id().into()))
+		.asset((Here, transfer_amount))
+		.fee((Here, fees_amount))
+		.remote_fee((Parent, 10 * WND_CENTS))
+		.beneficiary(receiver.clone())
+		.build::<<CustomPara as Chain>::RuntimeCall>()
+		.expect("the asset hub takes teleports of our token");
+	assert_eq!(xcm, expected);
+
+	// The program reads back the same from hex and JSON, like one found on a block explorer.
+	common::assert_round_trips(&xcm);
+
 	// This lets us execute calls on `CustomPara`.
 	// It's the main feature provided by the XCM emulator.
 	CustomPara::execute_with(|| {
@@ -89,6 +106,9 @@ fn cross_chain_transfer() {
 			Box::new(VersionedXcm::from(xcm)),
 			Weight::MAX,
 		));
+		// The whole program ran and a message left for the asset hub.
+		CustomPara::assert_xcm_pallet_attempted_complete();
+		CustomPara::assert_xcm_pallet_sent(Location::new(1, [Parachain(1000)]));
 
 		// We check that `transfer_amount` actually left the sender's account.
 		assert_eq!(
@@ -124,12 +144,8 @@ fn transfer_n_times() {
 	let (sender, receiver) = common::setup(initial_wnd_balance, initial_para_balance);
 	let transfer_amount = 1 * PARA_UNITS;
 
-	// `WithdrawAsset` parameters.
-	let assets_to_withdraw: Assets = (Here, transfer_amount).into();
-
 	// `PayFees` parameters.
 	let fees_amount = 10 * PARA_CENTS;
-	let fees_assets: Asset = (Here, fees_amount).into();
 
 	// `InitiateTransfer` parameters.
 	// Where we want to go.
@@ -157,32 +173,39 @@ fn transfer_n_times() {
 	let preserve_origin = false;
 	let transfer_assets = vec![AssetTransferFilter::Teleport(Wild(AllCounted(1)))];
 
-	// We recursively go forwards and backwards.
+	// We recursively go forwards and backwards, from the last hop to the second one.
 	// This is our base case.
-	let mut xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder_unsafe()
-		.deposit_asset(AllCounted(1), receiver.clone());
+	let mut xcm = Xcm::<()>::builder_unsafe().deposit_asset(AllCounted(1), receiver.clone());
 	// Then we loop and assemble our XCM.
-	for _ in 0..number_of_hops {
-		xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder_unsafe().initiate_transfer(
+	for _ in 1..number_of_hops {
+		xcm = Xcm::<()>::builder_unsafe().initiate_transfer(
 			if is_returning { return_destination.clone() } else { destination.clone() },
 			if is_returning { remote_fees_returning.clone() } else { remote_fees.clone() },
 			preserve_origin,
 			transfer_assets.clone(),
-			xcm.build().clone().into(),
+			xcm.build(),
 		);
 		is_returning = !is_returning;
 	}
-	// We finally build it.
-	let recursive_xcm = xcm.build();
-	let mut xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder_unsafe()
-		.withdraw_asset(assets_to_withdraw)
-		.pay_fees(fees_assets)
-		.build();
-	xcm.inner_mut().extend(recursive_xcm.into_iter());
+	// The first hop withdraws the assets, which `TransferBuilder` does for us.
+	// The rest of the journey is executed on the asset hub, before anything left there
+	// would be deposited.
+	let xcm = TransferBuilder::from(Parachain(CustomPara::para_id().into()))
+		.to(Parachain(AssetHubWestend::para_id().into()))
+		.asset((Here, transfer_amount))
+		.fee((Here, fees_amount))
+		.remote_fee((Here, remote_fees_amount))
+		.remote_xcm(xcm.build())
+		.beneficiary(receiver.clone())
+		.build::<<CustomPara as Chain>::RuntimeCall>()
+		.expect("the asset hub takes teleports of our token");
+
+	common::assert_round_trips(&xcm);
 
 	// We check that the `transfer_amount` was transferred out of the sender's
 	// account.
-	CustomPara::execute_with(|| {
+	// We capture this block so we can follow the message afterwards.
+	CustomPara::execute_and_capture(|| {
 		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
 			<CustomPara as Chain>::RuntimeOrigin::signed(sender.clone()),
 			Box::new(VersionedXcm::from(xcm)),
@@ -195,15 +218,25 @@ fn transfer_n_times() {
 			initial_para_balance - transfer_amount
 		);
 	});
-
-	// We need to do this to let the message in `AssetHubWestend` be processed
-	// and the new one forwarded back to `CustomPara`.
-	AssetHubWestend::execute_with(|| {});
-	// We poke the process for every message.
-	CustomPara::execute_with(|| {});
-	AssetHubWestend::execute_with(|| {});
-	CustomPara::execute_with(|| {});
-	// Until we reach the final destination.
+	// The router gave the message a topic, which is kept on every hop.
+	let topic = MessageLog::get()[0].topic.expect("`WithUniqueTopic` sets a topic");
+
+	// Each hop is only processed in the next block of its destination, so we keep
+	// producing blocks on every chain until all messages are processed.
+	// This works no matter how many hops there are.
+	drain_queues(10).unwrap();
+
+	// We can now see the journey of the message, chain by chain.
+	let timeline = Timeline::follow(topic);
+	assert!(timeline.is_success(), "{timeline}");
+	// The hop where it started and one more for every chain it went to.
+	assert_eq!(timeline.hops.len(), number_of_hops + 1, "{timeline}");
+	// Every chain on the way can price executing its part of the message.
+	for hop in &timeline.hops[1..] {
+		let processed = hop.processed.as_ref().expect("only the first hop isn't processed");
+		assert!(processed.estimated_execution_fee.is_some(), "{timeline}");
+	}
+	// Once we reach the final destination.
 	// Here, we check that the account on asset hub receives the funds minus fees from
 	// all the hops.
 	AssetHubWestend::execute_with(|| {
@@ -221,6 +254,112 @@ fn transfer_n_times() {
 	});
 }
 
+// Scenario:
+// The same journey as `transfer_n_times`, but planned instead of assembled by hand.
+// Every chain on the way is asked what it charges, so there are no fees to guess.
+// This time the asset hub is paid with `WND`, which travels along with our token.
+#[test]
+fn transfer_n_times_with_planned_fees() {
+	let initial_wnd_balance = 10 * WND_UNITS;
+	let initial_para_balance = 10 * PARA_UNITS;
+	let (sender, receiver) = common::setup(initial_wnd_balance, initial_para_balance);
+	let transfer_amount = 1 * PARA_UNITS;
+
+	// Chains and assets are relative to the relay chain, so `Here` is `WND`.
+	let custom_para = Parachain(CustomPara::para_id().into());
+	let asset_hub = Parachain(AssetHubWestend::para_id().into());
+	let route = RoutePlanner::from(custom_para)
+		.asset((custom_para, transfer_amount))
+		.fee_asset(custom_para)
+		.hop(asset_hub, Here)
+		.hop(custom_para, custom_para)
+		.hop(asset_hub, Here)
+		.beneficiary(receiver.clone())
+		.plan::<<CustomPara as Chain>::RuntimeCall>()
+		.expect("every chain on the way can size its fees");
+
+	common::assert_round_trips(&route.xcm);
+
+	CustomPara::execute_and_capture(|| {
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender.clone()),
+			Box::new(VersionedXcm::from(route.xcm.clone())),
+			Weight::MAX,
+		));
+
+		type Balances = <CustomPara as CustomParaPallet>::Balances;
+		type ForeignAssets = <CustomPara as CustomParaPallet>::ForeignAssets;
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&sender),
+			initial_para_balance - transfer_amount
+		);
+		// Only what pays the asset hub is withdrawn.
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::balance(Location::parent(), &sender),
+			initial_wnd_balance - route.fees_in(Here)
+		);
+	});
+	let topic = MessageLog::get()[0].topic.expect("`WithUniqueTopic` sets a topic");
+	drain_queues(10).unwrap();
+
+	let timeline = Timeline::follow(topic);
+	assert!(timeline.is_success(), "{timeline}");
+	assert_eq!(timeline.hops.len(), 4, "{timeline}");
+	// All the `WND` was spent on the way, only our token arrives.
+	assert_eq!(route.delivered.len(), 1);
+	AssetHubWestend::execute_with(|| {
+		type ForeignAssets = <AssetHubWestend as AssetHubWestendPallet>::ForeignAssets;
+		let balance = <ForeignAssets as fungibles::Inspect<_>>::balance(
+			Location::new(1, [custom_para]),
+			&receiver,
+		);
+		assert_eq!(balance, transfer_amount - route.fees_in(custom_para));
+	});
+}
+
+// Scenario:
+// A route is planned while a transfer is still on its way to the asset hub.
+// Planning only reads the chains, so the transfer stays queued until we drain the queues.
+#[test]
+fn planning_leaves_queued_messages_alone() {
+	let (sender, receiver) = common::setup(10 * WND_UNITS, 10 * PARA_UNITS);
+	let custom_para = Parachain(CustomPara::para_id().into());
+	let asset_hub = Parachain(AssetHubWestend::para_id().into());
+	let planner = RoutePlanner::from(custom_para)
+		.asset((custom_para, 1 * PARA_UNITS))
+		.fee_asset(custom_para)
+		.hop(asset_hub, Here)
+		.beneficiary(receiver.clone());
+	let route = planner.clone().plan::<<CustomPara as Chain>::RuntimeCall>().unwrap();
+
+	CustomPara::execute_and_capture(|| {
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender),
+			Box::new(VersionedXcm::from(route.xcm.clone())),
+			Weight::MAX,
+		));
+	});
+	assert!(has_pending_messages());
+	let block_numbers = common::block_numbers();
+
+	assert!(planner.plan::<<CustomPara as Chain>::RuntimeCall>().is_ok());
+	assert!(has_pending_messages());
+	assert_eq!(common::block_numbers(), block_numbers);
+	let received = || {
+		AssetHubWestend::ext_wrapper(|| {
+			type ForeignAssets = <AssetHubWestend as AssetHubWestendPallet>::ForeignAssets;
+			<ForeignAssets as fungibles::Inspect<_>>::balance(
+				Location::new(1, [custom_para]),
+				&receiver,
+			)
+		})
+	};
+	assert_eq!(received(), 0);
+
+	drain_queues(10).unwrap();
+	assert!(received() > 0);
+}
+
 // Scenario:
 // An account on `CustomPara` wants to execute a call on `AssetHubWestend`.
 // For this use case, we have the `Transact` instruction.
@@ -232,14 +371,7 @@ fn transfer_and_transact() {
 	let initial_para_balance = 10 * PARA_UNITS;
 	let (sender, receiver) = common::setup(initial_wnd_balance, initial_para_balance);
 	let transfer_amount = 1 * PARA_UNITS;
-
-	// `WithdrawAsset` parameters.
-	let assets_to_withdraw =
-		vec![(Here, transfer_amount).into(), (Parent, 10 * WND_UNITS).into()];
-
-	// `PayFees` parameters.
 	let fees_amount = 10 * PARA_CENTS;
-	let fees_assets: Asset = (Here, fees_amount).into();
 
 	// `Transact` parameters (remember this is on AssetHubWestend!).
 	// How to convert the location into a FRAME origin.
@@ -260,32 +392,23 @@ fn transfer_and_transact() {
 	})
 	.encode();
 
-	// `InitiateTransfer` parameters.
-	let destination = Location::new(1, [Parachain(1000)]);
-	let remote_fees =
-		AssetTransferFilter::ReserveWithdraw(Definite((Parent, 10 * WND_CENTS).into()));
-	// This time we NEED to preserve the origin.
-	// If not, the Transact won't know how to get a FRAME origin
-	// to execute the call.
-	let preserve_origin = true;
-	let transfer_assets = vec![AssetTransferFilter::Teleport(Wild(AllCounted(1)))];
-	let remote_xcm = Xcm::<()>::builder_unsafe()
-		.transact(origin_kind, fallback_max_weight, call)
-		.deposit_asset(AllCounted(1), receiver.clone())
-		.build();
-
-	// We assemble the XCM with all the previous values.
-	let xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder()
-		.withdraw_asset(assets_to_withdraw)
-		.pay_fees(fees_assets)
-		.initiate_transfer(
-			destination,
-			remote_fees,
-			preserve_origin,
-			transfer_assets,
-			remote_xcm,
-		)
-		.build();
+	// We transfer our token to the asset hub, paying fees there with `WND`, and the call is
+	// executed before the assets are deposited.
+	let xcm = TransferBuilder::from(Parachain(CustomPara::para_id().into()))
+		.to(Parachain(AssetHubWestend::para_id().into()))
+		.asset((Here, transfer_amount))
+		.fee((Here, fees_amount))
+		.remote_fee((Parent, 10 * WND_CENTS))
+		// This time we NEED to preserve the origin.
+		// If not, the Transact won't know how to get a FRAME origin
+		// to execute the call.
+		.preserve_origin(true)
+		.remote_xcm(Xcm::builder_unsafe().transact(origin_kind, fallback_max_weight, call).build())
+		.beneficiary(receiver.clone())
+		.build::<<CustomPara as Chain>::RuntimeCall>()
+		.expect("the asset hub takes teleports of our token and is the reserve of `WND`");
+
+	common::assert_round_trips(&xcm);
 
 	// We execute the XCM and assert that the `transfer_amount` is taken
 	// out of the senders account.
@@ -334,37 +457,28 @@ fn transfer_and_swap() {
 	let initial_para_balance = 100 * PARA_UNITS;
 	let (sender, _) = common::setup(initial_wnd_balance, initial_para_balance);
 	let transfer_amount = 23 * PARA_UNITS;
-
-	let assets_to_withdraw: Assets = (Here, transfer_amount).into();
-
 	let fees_amount = 10 * PARA_CENTS;
-	let fees_asset: Asset = (Here, fees_amount).into();
 
-	let destination: Location = (Parent, Parachain(1000)).into();
-	let remote_fees =
-		Some(AssetTransferFilter::Teleport(Definite((Here, 20 * PARA_CENTS).into())));
-	let preserve_origin = false;
-	let assets_to_transfer = vec![AssetTransferFilter::Teleport(Wild(AllCounted(1)))];
-	let remote_xcm = Xcm::<()>::builder_unsafe()
-		.exchange_asset(
-			Wild(AllCounted(1)),
-			(Parent, 10 * WND_UNITS),
-			true, // Maximal.
-		)
-		.deposit_asset(AllCounted(1), sender.clone())
-		.build();
-
-	let xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder()
-		.withdraw_asset(assets_to_withdraw)
-		.pay_fees(fees_asset)
-		.initiate_transfer(
-			destination,
-			remote_fees,
-			preserve_origin,
-			assets_to_transfer,
-			remote_xcm,
+	// The asset hub is trusted with teleports of our token, for the fees too.
+	let xcm = TransferBuilder::from(Parachain(CustomPara::para_id().into()))
+		.to(Parachain(AssetHubWestend::para_id().into()))
+		.asset((Here, transfer_amount))
+		.fee((Here, fees_amount))
+		.remote_fee((Here, 20 * PARA_CENTS))
+		// Once there, our token is swapped for as much `WND` as possible, at least 10.
+		.remote_xcm(
+			Xcm::builder_unsafe()
+				.exchange_asset(
+					Wild(AllCounted(1)),
+					(Parent, 10 * WND_UNITS),
+					true, // Maximal.
+				)
+				.build(),
 		)
-		.build();
+		.beneficiary(sender.clone())
+		.build::<<CustomPara as Chain>::RuntimeCall>()
+		.expect("the asset hub takes teleports of our token");
+	common::assert_round_trips(&xcm);
 
 	// We get the initial WND amount so we can compare it later.
 	let initial_wnd_on_ah = AssetHubWestend::execute_with(|| {
@@ -397,14 +511,12 @@ fn transfer_swap_and_back() {
 	let (sender, _) = common::setup(initial_wnd_balance, initial_para_balance);
 	let transfer_amount = 23 * PARA_UNITS;
 	let fees_amount = 10 * PARA_CENTS;
-	let xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder()
-		.withdraw_asset(vec![(Here, transfer_amount).into()])
-		.pay_fees((Here, fees_amount))
-		.initiate_transfer(
-			(Parent, Parachain(1000)),
-			AssetTransferFilter::Teleport(Definite((Here, 20 * PARA_CENTS).into())),
-			false,
-			vec![AssetTransferFilter::Teleport(Wild(AllCounted(1)))],
+	let xcm = TransferBuilder::from(Parachain(CustomPara::para_id().into()))
+		.to(Parachain(AssetHubWestend::para_id().into()))
+		.asset((Here, transfer_amount))
+		.fee((Here, fees_amount))
+		.remote_fee((Here, 20 * PARA_CENTS))
+		.remote_xcm(
 			Xcm::builder_unsafe()
 				// After the exchange...
 				.exchange_asset(
@@ -415,9 +527,7 @@ fn transfer_swap_and_back() {
 				// ..we just send all the assets back...
 				.initiate_transfer(
 					(Parent, Parachain(2000)),
-					AssetTransferFilter::ReserveDeposit(Definite(
-						(Parent, 50 * WND_CENTS).into(),
-					)),
+					AssetTransferFilter::ReserveDeposit(Definite((Parent, 50 * WND_CENTS).into())),
 					false,
 					vec![AssetTransferFilter::ReserveDeposit(Wild(AllCounted(1)))],
 					Xcm::builder_unsafe()
@@ -427,7 +537,12 @@ fn transfer_swap_and_back() {
 				)
 				.build(),
 		)
-		.build();
+		// Nothing is left on the asset hub, but it would go to the sender there.
+		.beneficiary(sender.clone())
+		.build::<<CustomPara as Chain>::RuntimeCall>()
+		.expect("the asset hub takes teleports of our token");
+
+	common::assert_round_trips(&xcm);
 
 	CustomPara::execute_with(|| {
 		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
@@ -437,7 +552,7 @@ fn transfer_swap_and_back() {
 		));
 	});
 	// We let the message in `AssetHubWestend` process.
-	AssetHubWestend::execute_with(|| {});
+	drain_queues(10).unwrap();
 	CustomPara::execute_with(|| {
 		// We check if we got the WND back.
 		type ForeignAssets = <CustomPara as CustomParaPallet>::ForeignAssets;
@@ -0,0 +1,84 @@
+//! Tests for the `NetworkGenesis` builder.
+
+use emulator::prelude::*;
+use frame_support::traits::{fungible, fungibles};
+use xcm::prelude::*;
+
+type Balances = <CustomPara as CustomParaPallet>::Balances;
+type ForeignAssets = <CustomPara as CustomParaPallet>::ForeignAssets;
+
+/// `PARA` and `WND` balances of `who`, read without producing a block.
+fn balances(who: &AccountId) -> (u128, u128) {
+	CustomPara::ext_wrapper(|| {
+		(
+			<Balances as fungible::Inspect<_>>::balance(who),
+			<ForeignAssets as fungibles::Inspect<_>>::balance(Location::parent(), who),
+		)
+	})
+}
+
+// Balances are added to what the genesis preset gives, unless they're set.
+#[test]
+fn balances_are_added_unless_set() {
+	let sender = CustomParaSender::get();
+	let receiver = CustomParaReceiver::get();
+	let (sender_para, sender_wnd) = balances(&sender);
+	// `WND` is registered by the genesis preset.
+	NetworkGenesis::new()
+		.custom_para(
+			ChainGenesis::new()
+				.balance(sender.clone(), 5 * PARA_UNITS)
+				.foreign_balance(Location::parent(), sender.clone(), 5 * WND_UNITS)
+				.set_balance(receiver.clone(), 3 * PARA_UNITS)
+				.set_foreign_balance(Location::parent(), receiver.clone(), 3 * WND_UNITS),
+		)
+		.apply();
+
+	assert_eq!(balances(&sender), (sender_para + 5 * PARA_UNITS, sender_wnd + 5 * WND_UNITS));
+	assert_eq!(balances(&receiver), (3 * PARA_UNITS, 3 * WND_UNITS));
+}
+
+// Foreign assets, sovereign accounts and pools are all there after `apply`.
+#[test]
+fn assets_sovereign_accounts_and_pools_are_created() {
+	type Balances = <AssetHubWestend as AssetHubWestendPallet>::Balances;
+	type ForeignAssets = <AssetHubWestend as AssetHubWestendPallet>::ForeignAssets;
+	type AssetConversion = <AssetHubWestend as AssetHubWestendPallet>::AssetConversion;
+
+	let provider = AssetHubWestendSender::get();
+	let receiver = AssetHubWestendReceiver::get();
+	let para = AssetHubWestend::sibling_location_of(CustomPara::para_id());
+	let sovereign = AssetHubWestend::sovereign_account_id_of(para.clone());
+	let sovereign_before =
+		AssetHubWestend::ext_wrapper(|| <Balances as fungible::Inspect<_>>::balance(&sovereign));
+	NetworkGenesis::new()
+		.asset_hub(
+			ChainGenesis::new()
+				.foreign_asset(para.clone(), provider.clone(), 1)
+				.sovereign_balance(para.clone(), 7 * WND_UNITS)
+				.foreign_balance(para.clone(), provider.clone(), 20 * PARA_UNITS)
+				.set_foreign_balance(para.clone(), receiver.clone(), 3 * PARA_UNITS)
+				.pool(
+					(Location::parent(), 5 * WND_UNITS),
+					(para.clone(), 10 * PARA_UNITS),
+					provider,
+				),
+		)
+		.apply();
+
+	AssetHubWestend::ext_wrapper(|| {
+		assert!(<ForeignAssets as fungibles::Inspect<_>>::asset_exists(para.clone()));
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&sovereign),
+			sovereign_before + 7 * WND_UNITS
+		);
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::balance(para.clone(), &receiver),
+			3 * PARA_UNITS
+		);
+		assert_eq!(
+			AssetConversion::get_reserves(Location::parent(), para).expect("the pool exists"),
+			(5 * WND_UNITS, 10 * PARA_UNITS)
+		);
+	});
+}
@@ -0,0 +1,128 @@
+//! Tests for handling HRMP channel notifications from the relay chain.
+
+use emulator::prelude::*;
+use frame_support::assert_ok;
+use parachain_runtime::pallets::hrmp_channels;
+use polkadot_parachain_primitives::primitives::{HrmpChannelId, Id as ParaId};
+use polkadot_runtime_parachains::hrmp;
+
+const MAX_CAPACITY: u32 = 8;
+const MAX_MESSAGE_SIZE: u32 = 8192;
+
+/// The origin a parachain gets when calling into the relay chain.
+fn parachain_origin(para_id: ParaId) -> <Westend as Chain>::RuntimeOrigin {
+	polkadot_runtime_parachains::Origin::Parachain(para_id).into()
+}
+
+// Scenario:
+// A trusted sibling, with no channel to our `CustomPara` yet, wants to open one.
+// The relay chain lets us know and we accept it, requesting a channel back, without any
+// governance involved.
+#[test]
+fn open_channel_request_from_trusted_sibling_is_accepted() {
+	let sibling = SiblingPara::para_id();
+	let custom_para = CustomPara::para_id();
+	// Both sovereign accounts on the relay chain need funds for the channel deposits.
+	// Ours also pays the fees for accepting and opening the channel back.
+	Westend::fund_para_sovereign(1_000 * WND_UNITS, sibling);
+	Westend::fund_para_sovereign(1_000 * WND_UNITS, custom_para);
+
+	// The sibling requests a channel to us.
+	Westend::execute_with(|| {
+		type Hrmp = <Westend as WestendPallet>::Hrmp;
+		type RuntimeEvent = <Westend as Chain>::RuntimeEvent;
+		assert_ok!(Hrmp::hrmp_init_open_channel(
+			parachain_origin(sibling),
+			custom_para,
+			MAX_CAPACITY,
+			MAX_MESSAGE_SIZE,
+		));
+		assert_expected_events!(
+			Westend,
+			vec![
+				RuntimeEvent::Hrmp(hrmp::Event::OpenChannelRequested { sender, recipient, .. }) => {
+					sender: *sender == sibling,
+					recipient: *recipient == custom_para,
+				},
+			]
+		);
+	});
+
+	// The relay chain notifies us and we accept right away.
+	CustomPara::execute_with(|| {
+		type RuntimeEvent = <CustomPara as Chain>::RuntimeEvent;
+		assert_expected_events!(
+			CustomPara,
+			vec![
+				RuntimeEvent::HrmpChannels(hrmp_channels::Event::OpenRequestAccepted { sender }) => {
+					sender: *sender == sibling,
+				},
+			]
+		);
+	});
+
+	// The relay chain processes our acceptance and our request for the opposite channel.
+	Westend::execute_with(|| {
+		type RuntimeEvent = <Westend as Chain>::RuntimeEvent;
+		assert_expected_events!(
+			Westend,
+			vec![
+				RuntimeEvent::Hrmp(hrmp::Event::OpenChannelAccepted { sender, recipient }) => {
+					sender: *sender == sibling,
+					recipient: *recipient == custom_para,
+				},
+				RuntimeEvent::Hrmp(hrmp::Event::OpenChannelRequested { sender, recipient, .. }) => {
+					sender: *sender == custom_para,
+					recipient: *recipient == sibling,
+				},
+			]
+		);
+	});
+
+	// The sibling accepts the channel back to it.
+	Westend::execute_with(|| {
+		type Hrmp = <Westend as WestendPallet>::Hrmp;
+		assert_ok!(Hrmp::hrmp_accept_open_channel(parachain_origin(sibling), custom_para));
+	});
+	CustomPara::execute_with(|| {
+		type RuntimeEvent = <CustomPara as Chain>::RuntimeEvent;
+		assert_expected_events!(
+			CustomPara,
+			vec![
+				RuntimeEvent::HrmpChannels(hrmp_channels::Event::ChannelAccepted { recipient }) => {
+					recipient: *recipient == sibling,
+				},
+			]
+		);
+	});
+
+	// Channels are only opened at session change, so we force it.
+	Westend::force_process_hrmp_open(sibling, custom_para);
+	Westend::force_process_hrmp_open(custom_para, sibling);
+
+	// When the sibling closes its channel, we get notified as well.
+	Westend::execute_with(|| {
+		type Hrmp = <Westend as WestendPallet>::Hrmp;
+		assert_ok!(Hrmp::hrmp_close_channel(
+			parachain_origin(sibling),
+			HrmpChannelId { sender: sibling, recipient: custom_para },
+		));
+	});
+	CustomPara::execute_with(|| {
+		type RuntimeEvent = <CustomPara as Chain>::RuntimeEvent;
+		assert_expected_events!(
+			CustomPara,
+			vec![
+				RuntimeEvent::HrmpChannels(hrmp_channels::Event::ChannelClosing {
+					initiator,
+					sender,
+					recipient,
+				}) => {
+					initiator: *initiator == sibling,
+					sender: *sender == sibling,
+					recipient: *recipient == custom_para,
+				},
+			]
+		);
+	});
+}
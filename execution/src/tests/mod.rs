@@ -11,6 +11,26 @@ mod asset_transactor;
 mod barrier;
 // Tests for configuring different reserves and teleporters.
 mod reserves_and_teleports;
+// Tests for filtering which calls can be dispatched with `Transact`.
+mod call_filter;
+// Tests for handling HRMP channel notifications from the relay chain.
+mod hrmp;
+// Tests for inspecting the XCMs recorded on our parachain.
+mod recorder;
+// Tests for transfers between two instances of our parachain.
+mod siblings;
+// Tests for transfers to another network, over a bridge.
+mod bridge;
+// Tests for transfers to a chain with 20 byte accounts.
+mod evm;
+// Tests for routes that swap assets on a DEX parachain on the way.
+mod dex;
+// Tests for working out which account a location gets on each chain.
+mod sovereign;
+// Tests for declaring the state the network starts from.
+mod genesis;
+// Tests for the messages captured between chains.
+mod capture;
 
 // Common helpers used throughout the tests.
 mod common;
@@ -0,0 +1,76 @@
+//! Tests for the `XcmRecorderApi`, which tells us what an XCM did on our parachain.
+
+use emulator::prelude::*;
+use parachain_runtime::{
+	apis::{InstructionOutcome, XcmRecorderApi},
+	Block,
+};
+use xcm::prelude::*;
+
+type Runtime = <CustomPara as Chain>::Runtime;
+type RuntimeCall = <CustomPara as Chain>::RuntimeCall;
+
+// Scenario:
+// The asset hub sends us a message that tries to use `WND` its sovereign account doesn't have.
+// Support wants to know which instruction failed and why.
+#[test]
+fn recorded_message_shows_failing_instruction() {
+	let asset_hub = Location::new(1, [Parachain(AssetHubWestend::para_id().into())]);
+	let fees: Asset = (Parent, 10 * WND_CENTS).into();
+	let xcm = Xcm::<RuntimeCall>::builder()
+		.withdraw_asset(fees.clone())
+		.buy_execution(fees, Unlimited)
+		.deposit_asset(AllCounted(1), CustomParaReceiver::get())
+		.build();
+
+	CustomPara::execute_with(|| {
+		let recorded = <Runtime as XcmRecorderApi<Block, RuntimeCall>>::recorded_xcm_for_message(
+			VersionedLocation::from(asset_hub),
+			VersionedXcm::from(xcm.clone()),
+		)
+		.unwrap();
+
+		// We get back the exact program...
+		assert_eq!(recorded.program, VersionedXcm::from(Xcm::<()>::from(xcm)));
+		// ...and see it stopped at the very first instruction.
+		assert_eq!(
+			recorded.instructions,
+			vec![
+				InstructionOutcome::Failed(pallet_xcm::ExecutionError::FailedToTransactAsset),
+				InstructionOutcome::NotExecuted,
+				InstructionOutcome::NotExecuted,
+			]
+		);
+		assert!(recorded.forwarded_xcms.is_empty());
+	});
+}
+
+// Scenario:
+// The same message, but this time the asset hub's sovereign account has enough `WND`.
+#[test]
+fn recorded_message_shows_every_instruction_executed() {
+	let asset_hub = Location::new(1, [Parachain(AssetHubWestend::para_id().into())]);
+	let fees: Asset = (Parent, 10 * WND_CENTS).into();
+	let xcm = Xcm::<RuntimeCall>::builder()
+		.withdraw_asset(fees.clone())
+		.buy_execution(fees, Unlimited)
+		.deposit_asset(AllCounted(1), CustomParaReceiver::get())
+		.build();
+	// `WND` is registered in genesis, with the sender as its owner.
+	CustomPara::mint_foreign_asset(
+		<CustomPara as Chain>::RuntimeOrigin::signed(CustomParaSender::get()),
+		Location::parent(),
+		CustomPara::sovereign_account_id_of(asset_hub.clone()),
+		WND_UNITS,
+	);
+
+	CustomPara::execute_with(|| {
+		let recorded = <Runtime as XcmRecorderApi<Block, RuntimeCall>>::recorded_xcm_for_message(
+			VersionedLocation::from(asset_hub),
+			VersionedXcm::from(xcm),
+		)
+		.unwrap();
+
+		assert_eq!(recorded.instructions, vec![InstructionOutcome::Executed; 3]);
+	});
+}
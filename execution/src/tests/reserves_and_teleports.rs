@@ -1,7 +1,10 @@
 //! Tests for configuring IsReserve and IsTeleporter.
 
 use emulator::prelude::*;
-use frame_support::{assert_ok, traits::{fungible, fungibles}};
+use frame_support::{assert_ok, traits::{fungible, fungibles, ContainsPair}};
+use parachain_runtime::{
+	configs::xcm_config::XcmConfig, genesis_config_presets::ENDOWMENT, pallets::xcm_failures,
+};
 use xcm::{prelude::*, latest::AssetTransferFilter};
 
 #[test]
@@ -31,6 +34,15 @@ fn teleport_of_para_from_asset_hub_works() {
                 .build()
         )
         .build();
+    // We trust the asset hub as a teleporter for `PARA`, so that's what the builder picks.
+    let built = TransferBuilder::from(Parachain(AssetHubWestend::para_id().into()))
+        .to(Parachain(CustomPara::para_id().into()))
+        .asset((para_location.clone(), transfer_amount))
+        .fee((Parent, local_fees_amount))
+        .remote_fee((para_location.clone(), remote_fees_amount))
+        .beneficiary(receiver.clone())
+        .build::<<AssetHubWestend as Chain>::RuntimeCall>();
+    assert_eq!(built, Ok(xcm.clone()));
 	AssetHubWestend::force_create_foreign_asset(
 		para_location.clone(),
 		AssetHubWestendSender::get(),
@@ -60,7 +72,8 @@ fn teleport_of_para_from_asset_hub_works() {
     CustomPara::execute_with(|| {
         type Balances = <CustomPara as CustomParaPallet>::Balances;
         let balance = <Balances as fungible::Inspect<_>>::balance(&receiver);
-        assert_eq!(balance, transfer_amount - remote_fees_amount);
+        // The receiver already had some `PARA` from genesis.
+        assert_eq!(balance, ENDOWMENT + transfer_amount - remote_fees_amount);
     });
 }
 
@@ -89,21 +102,17 @@ fn reserve_deposit_wnd_from_asset_hub_works() {
                 .build()
         )
         .build();
-    // We need to register `WND` on the parachain.
-	CustomPara::force_create_foreign_asset(
-		Location::parent(),
-		CustomParaSender::get(),
-		false,
-		1,
-		Vec::new(),
-	);
-	// To cover ED on receiver.
-	CustomPara::execute_with(|| {
-		type Balances = <CustomPara as CustomParaPallet>::Balances;
-		assert_ok!(
-			<Balances as fungible::Mutate<_>>::mint_into(&receiver, 1 * PARA_UNITS)
-		);
-	});
+    // For `WND`, we trust the asset hub as a reserve.
+    let built = TransferBuilder::from(Parachain(AssetHubWestend::para_id().into()))
+        .to(Parachain(CustomPara::para_id().into()))
+        .asset((Parent, transfer_amount + local_fees_amount))
+        .fee((Parent, local_fees_amount))
+        .remote_fee((Parent, remote_fees_amount))
+        .beneficiary(receiver.clone())
+        .build::<<AssetHubWestend as Chain>::RuntimeCall>();
+    assert_eq!(built, Ok(xcm.clone()));
+    // `WND` is registered on the parachain and the receiver has `PARA` to cover the ED,
+    // both from genesis.
     AssetHubWestend::execute_with(|| {
         type Balances = <AssetHubWestend as AssetHubWestendPallet>::Balances;
         assert_ok!(<Balances as fungible::Mutate<_>>::mint_into(
@@ -123,3 +132,164 @@ fn reserve_deposit_wnd_from_asset_hub_works() {
         assert_eq!(balance, transfer_amount - remote_fees_amount);
     });
 }
+
+#[test]
+fn teleport_of_wnd_from_asset_hub_fails() {
+	let initial_wnd_balance = 20 * WND_UNITS;
+	let transfer_amount = 10 * WND_UNITS;
+	let local_fees_amount = 10 * WND_CENTS;
+	let remote_fees_amount = 10 * WND_CENTS;
+	let para_location = Location::new(1, [Parachain(CustomPara::para_id().into())]);
+	let assets_to_withdraw: Assets =
+		vec![(Parent, transfer_amount + local_fees_amount).into()].into();
+	let sender = AssetHubWestendSender::get();
+	let receiver = CustomParaReceiver::get();
+	// We only trust the asset hub as a reserve for `WND`, not as a teleporter.
+	let xcm = Xcm::<<AssetHubWestend as Chain>::RuntimeCall>::builder()
+		.withdraw_asset(assets_to_withdraw)
+		.pay_fees((Parent, local_fees_amount))
+		.initiate_transfer(
+			para_location.clone(),
+			AssetTransferFilter::Teleport(Definite((Parent, remote_fees_amount).into())),
+			false,
+			vec![AssetTransferFilter::Teleport(Wild(AllCounted(1)))],
+			Xcm::<()>::builder_unsafe()
+				.deposit_asset(AllCounted(1), receiver.clone())
+				.build(),
+		)
+		.build();
+	// Both blocks are captured, so the failure can be drawn.
+	let message_id = AssetHubWestend::execute_and_capture(|| {
+		type Balances = <AssetHubWestend as AssetHubWestendPallet>::Balances;
+		type RuntimeEvent = <AssetHubWestend as Chain>::RuntimeEvent;
+		assert_ok!(<Balances as fungible::Mutate<_>>::mint_into(&sender, initial_wnd_balance));
+		assert_ok!(<AssetHubWestend as AssetHubWestendPallet>::PolkadotXcm::execute(
+			<AssetHubWestend as Chain>::RuntimeOrigin::signed(sender.clone()),
+			Box::new(VersionedXcm::from(xcm)),
+			Weight::MAX,
+		));
+		// We keep the topic of the message sent to our parachain.
+		<AssetHubWestend as AssetHubWestendPallet>::System::events()
+			.into_iter()
+			.find_map(|record| match record.event {
+				RuntimeEvent::PolkadotXcm(pallet_xcm::Event::Sent { message_id, .. }) =>
+					Some(message_id),
+				_ => None,
+			})
+			.expect("a message was sent to our parachain")
+	});
+
+	CustomPara::execute_and_capture(|| {
+		type RuntimeEvent = <CustomPara as Chain>::RuntimeEvent;
+		assert_expected_events!(
+			CustomPara,
+			vec![
+				// The very first instruction, `ReceiveTeleportedAsset`, fails.
+				RuntimeEvent::XcmFailures(xcm_failures::Event::ExecutionFailed {
+					origin,
+					index,
+					error,
+					message_id: failed_message_id,
+				}) => {
+					origin: *origin == Location::new(1, [Parachain(AssetHubWestend::para_id().into())]),
+					index: *index == 0,
+					error: *error == XcmError::UntrustedTeleportLocation,
+					failed_message_id: *failed_message_id == message_id,
+				},
+			]
+		);
+		type ForeignAssets = <CustomPara as CustomParaPallet>::ForeignAssets;
+		let balance = <ForeignAssets as fungibles::Inspect<_>>::balance(Parent.into(), &receiver);
+		assert_eq!(balance, 0);
+	});
+
+	// The message is highlighted as failed on our parachain in both diagrams.
+	let log = MessageLog::get().with_topic(message_id);
+	let mermaid = log.to_mermaid();
+	assert!(mermaid.contains("para_1000-xpara_2000"), "{mermaid}");
+	assert!(mermaid.contains("Note over para_2000: FAILED"), "{mermaid}");
+	let graphviz = log.to_graphviz();
+	assert!(graphviz.contains("para_1000 -> para_2000"), "{graphviz}");
+	assert!(graphviz.contains("color=red, fontcolor=red"), "{graphviz}");
+}
+
+// `TransferBuilder` picks transfer types from a table of who trusts whom.
+// What it says about a chain has to be what its `IsReserve` and `IsTeleporter` say.
+#[test]
+fn trust_table_mirrors_our_configuration() {
+	CustomPara::execute_with(|| {
+		assert_mirrors_table::<XcmConfig>(Parachain(CustomPara::para_id().into()).into())
+	});
+	SiblingPara::execute_with(|| {
+		assert_mirrors_table::<XcmConfig>(Parachain(SiblingPara::para_id().into()).into())
+	});
+	EvmPara::execute_with(|| {
+		assert_mirrors_table::<evm_parachain_runtime::configs::xcm_config::XcmConfig>(
+			Parachain(EvmPara::para_id().into()).into(),
+		)
+	});
+	DexPara::execute_with(|| {
+		assert_mirrors_table::<dex_parachain_runtime::configs::xcm_config::XcmConfig>(
+			Parachain(DexPara::para_id().into()).into(),
+		)
+	});
+	AssetHubWestend::execute_with(|| {
+		assert_mirrors_table::<asset_hub_westend_runtime::xcm_config::XcmConfig>(
+			Parachain(AssetHubWestend::para_id().into()).into(),
+		)
+	});
+}
+
+/// Checks every token from every other chain against `chain`'s configuration.
+/// Locations are relative to the relay chain, like the ones of the table.
+fn assert_mirrors_table<Config: xcm_executor::Config>(chain: Location) {
+	let table = TrustTable::westend();
+	// Every chain of the network.
+	let chains: Vec<Location> = vec![
+		Location::here(),
+		Parachain(AssetHubWestend::para_id().into()).into(),
+		Parachain(BridgeHubWestend::para_id().into()).into(),
+		Parachain(CustomPara::para_id().into()).into(),
+		Parachain(SiblingPara::para_id().into()).into(),
+		Parachain(EvmPara::para_id().into()).into(),
+		Parachain(DexPara::para_id().into()).into(),
+	];
+	// `WND` and the native tokens of the parachains.
+	let tokens: Vec<Location> = vec![
+		Location::here(),
+		Parachain(CustomPara::para_id().into()).into(),
+		Parachain(SiblingPara::para_id().into()).into(),
+		Parachain(EvmPara::para_id().into()).into(),
+		Parachain(DexPara::para_id().into()).into(),
+	];
+
+	for from in chains.iter().filter(|from| **from != chain) {
+		for token in &tokens {
+			let asset: Asset = (token.clone().reanchored(&chain, &Here).unwrap(), 1u128).into();
+			let from_here = from.clone().reanchored(&chain, &Here).unwrap();
+			assert_eq!(
+				Config::IsReserve::contains(&asset, &from_here),
+				table.trusts(&chain, token, from, Trust::Reserve),
+				"{chain:?}: reserve of {token:?} from {from:?}",
+			);
+			assert_eq!(
+				Config::IsTeleporter::contains(&asset, &from_here),
+				table.trusts(&chain, token, from, Trust::Teleporter),
+				"{chain:?}: teleport of {token:?} from {from:?}",
+			);
+		}
+	}
+}
+
+// No one in between is trusted, so `WND` can't go straight to another parachain.
+// It has to go through the asset hub, its reserve.
+#[test]
+fn transfer_builder_refuses_untrusted_transfers() {
+	let built = TransferBuilder::from(Parachain(CustomPara::para_id().into()))
+		.to(Parachain(EvmPara::para_id().into()))
+		.asset((Parent, 1 * WND_UNITS))
+		.fee((Parent, 10 * WND_CENTS))
+		.beneficiary(CustomParaReceiver::get())
+		.build::<()>();
+	assert_eq!(built, Err(TransferError::Untrusted { asset: Location::here() }));
+}
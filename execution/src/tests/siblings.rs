@@ -0,0 +1,159 @@
+//! Tests for transfers between `CustomPara` and `SiblingPara`, two instances of our runtime.
+
+use super::common;
+
+use emulator::prelude::*;
+use frame_support::{
+	assert_ok,
+	traits::tokens::{fungible, fungibles},
+	weights::Weight,
+};
+use xcm::{latest::AssetTransferFilter, prelude::*};
+
+/// Registers `PARA` from `CustomPara` as a foreign asset on `SiblingPara`.
+fn register_custom_para_token_on_sibling() -> Location {
+	let custom_para_from_sibling = SiblingPara::sibling_location_of(CustomPara::para_id());
+	SiblingPara::force_create_foreign_asset(
+		custom_para_from_sibling.clone(),
+		SiblingParaSender::get(),
+		false,
+		1,
+		Vec::new(),
+	);
+	custom_para_from_sibling
+}
+
+// Scenario:
+// A sender on `CustomPara` wants to send some `PARA` straight to `SiblingPara`.
+// `CustomPara` is the reserve of its own token, so no one else needs to be involved.
+#[test]
+fn reserve_transfer_to_sibling() {
+	let initial_wnd_balance = 10 * WND_UNITS;
+	let initial_para_balance = 10 * PARA_UNITS;
+	let (sender, _) = common::setup(initial_wnd_balance, initial_para_balance);
+	let receiver = SiblingParaReceiver::get();
+	let custom_para_from_sibling = register_custom_para_token_on_sibling();
+	let transfer_amount = 1 * PARA_UNITS;
+	let fees_amount = 10 * PARA_CENTS;
+	let remote_fees_amount = 10 * PARA_CENTS;
+
+	let destination = Location::new(1, [Parachain(SiblingPara::para_id().into())]);
+	// `SiblingPara` trusts us as the reserve of our token, so both fees and assets travel as
+	// reserve deposits.
+	// It takes our token for fees, since it runs the same runtime.
+	let xcm = TransferBuilder::from(Parachain(CustomPara::para_id().into()))
+		.to(Parachain(SiblingPara::para_id().into()))
+		.asset((Here, transfer_amount))
+		.fee((Here, fees_amount))
+		.remote_fee((Here, remote_fees_amount))
+		.beneficiary(receiver.clone())
+		.build::<<CustomPara as Chain>::RuntimeCall>()
+		.expect("`SiblingPara` trusts us with our token");
+
+	// We capture the messages sent in this block to check what went to the sibling.
+	CustomPara::execute_and_capture(|| {
+		type Balances = <CustomPara as CustomParaPallet>::Balances;
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender.clone()),
+			Box::new(VersionedXcm::from(xcm)),
+			Weight::MAX,
+		));
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&sender),
+			initial_para_balance - transfer_amount
+		);
+		// Everything that left the chain is backed by the sibling's sovereign account.
+		let sibling_sovereign_account = CustomPara::sovereign_account_id_of(destination);
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&sibling_sovereign_account),
+			transfer_amount - fees_amount
+		);
+	});
+
+	// Exactly one message went straight to the sibling, saying the assets are in its
+	// sovereign account.
+	let to_sibling = MessageLog::get().to(Parachain(SiblingPara::para_id().into()));
+	assert_eq!(to_sibling.len(), 1, "{}", to_sibling.pretty());
+	assert_eq!(to_sibling[0].transport, Transport::Hrmp);
+	assert!(to_sibling[0].contains(|instruction| matches!(instruction, ReserveAssetDeposited(..))));
+	// The same message can be drawn for design reviews.
+	assert!(to_sibling.to_mermaid().contains("para_2000->>para_2001"));
+
+	SiblingPara::execute_with(|| {
+		type ForeignAssets = <SiblingPara as SiblingParaPallet>::ForeignAssets;
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::balance(custom_para_from_sibling, &receiver),
+			transfer_amount - fees_amount - remote_fees_amount
+		);
+	});
+}
+
+// Scenario:
+// The same transfer, but going through the asset hub.
+// We teleport `PARA` to the asset hub, which then acts as a reserve for `SiblingPara`.
+// This is useful when there's no channel between the two siblings.
+#[test]
+fn transfer_to_sibling_through_asset_hub() {
+	let initial_wnd_balance = 10 * WND_UNITS;
+	let initial_para_balance = 10 * PARA_UNITS;
+	let (sender, _) = common::setup(initial_wnd_balance, initial_para_balance);
+	let receiver = SiblingParaReceiver::get();
+	let custom_para_from_sibling = register_custom_para_token_on_sibling();
+	let transfer_amount = 1 * PARA_UNITS;
+	let fees_amount = 10 * PARA_CENTS;
+	// The asset hub swaps these to `WND` to pay for its execution and for forwarding the
+	// message.
+	let asset_hub_fees_amount = 20 * PARA_CENTS;
+	let sibling_fees_amount = 10 * PARA_CENTS;
+
+	let asset_hub = Location::new(1, [Parachain(AssetHubWestend::para_id().into())]);
+	let sibling = Location::new(1, [Parachain(SiblingPara::para_id().into())]);
+	// Our token, as seen from the asset hub.
+	let para_token = Location::new(1, [Parachain(CustomPara::para_id().into())]);
+
+	// Second hop, executed on the asset hub.
+	let xcm_on_asset_hub = Xcm::<()>::builder_unsafe()
+		.initiate_transfer(
+			sibling,
+			AssetTransferFilter::ReserveDeposit(Definite((para_token, sibling_fees_amount).into())),
+			false,
+			vec![AssetTransferFilter::ReserveDeposit(Wild(AllCounted(1)))],
+			Xcm::<()>::builder_unsafe().deposit_asset(AllCounted(1), receiver.clone()).build(),
+		)
+		.build();
+	// First hop, executed locally.
+	let xcm = Xcm::<<CustomPara as Chain>::RuntimeCall>::builder()
+		.withdraw_asset((Here, transfer_amount))
+		.pay_fees((Here, fees_amount))
+		.initiate_transfer(
+			asset_hub,
+			AssetTransferFilter::Teleport(Definite((Here, asset_hub_fees_amount).into())),
+			false,
+			vec![AssetTransferFilter::Teleport(Wild(AllCounted(1)))],
+			xcm_on_asset_hub,
+		)
+		.build();
+
+	CustomPara::execute_with(|| {
+		type Balances = <CustomPara as CustomParaPallet>::Balances;
+		assert_ok!(<CustomPara as CustomParaPallet>::PolkadotXcm::execute(
+			<CustomPara as Chain>::RuntimeOrigin::signed(sender.clone()),
+			Box::new(VersionedXcm::from(xcm)),
+			Weight::MAX,
+		));
+		assert_eq!(
+			<Balances as fungible::Inspect<_>>::balance(&sender),
+			initial_para_balance - transfer_amount
+		);
+	});
+	// The asset hub needs a block to process the first hop and forward the second.
+	drain_queues(10).unwrap();
+
+	SiblingPara::execute_with(|| {
+		type ForeignAssets = <SiblingPara as SiblingParaPallet>::ForeignAssets;
+		assert_eq!(
+			<ForeignAssets as fungibles::Inspect<_>>::balance(custom_para_from_sibling, &receiver),
+			transfer_amount - fees_amount - asset_hub_fees_amount - sibling_fees_amount
+		);
+	});
+}
@@ -0,0 +1,72 @@
+//! Tests for the converter stacks in `emulator::sovereign`.
+//! The accounts are worked out by hand, from how each converter describes a location.
+
+use emulator::prelude::*;
+use xcm::prelude::*;
+
+#[test]
+fn relay_gives_its_children_a_para_account() {
+	let account = Converter::Relay.convert(&Location::new(0, [Parachain(2000)])).unwrap();
+	// `para` followed by the id.
+	assert_eq!(account.hex(), "0x70617261d0070000000000000000000000000000000000000000000000000000");
+	assert_eq!(
+		account.ss58(WESTEND_SS58_PREFIX),
+		"5Ec4AhPUwPeyTFyuhGuBbD224mY85LKLMSqSSo33JYWCazU4"
+	);
+}
+
+#[test]
+fn asset_hub_gives_its_siblings_a_sibl_account() {
+	let account = Converter::AssetHub.convert(&Location::new(1, [Parachain(2000)])).unwrap();
+	// `sibl` followed by the id.
+	assert_eq!(account.hex(), "0x7369626cd0070000000000000000000000000000000000000000000000000000");
+	assert_eq!(
+		account.ss58(WESTEND_SS58_PREFIX),
+		"5Eg2fntJ27qsari4FGrGhrMqKFDRnkNSR6UshkZYBGXmSuC8"
+	);
+}
+
+#[test]
+fn accounts_on_a_sibling_get_a_hashed_account() {
+	// The sender of `transfer_and_transact`, seen from the asset hub.
+	let location = Location::new(
+		1,
+		[Parachain(2000), AccountId32 { network: None, id: CustomParaSender::get().into() }],
+	);
+	// Both chains hash the same description, so they agree.
+	for converter in [Converter::AssetHub, Converter::CustomPara] {
+		let account = converter.convert(&location).unwrap();
+		assert_eq!(
+			account.hex(),
+			"0x86b163f3eeb29f4ce34a47d1713c834f90eb405bb57793e603456f8b5718b1c8",
+			"{converter}"
+		);
+		assert_eq!(
+			account.ss58(WESTEND_SS58_PREFIX),
+			"5F7JyCFcjQFvSiA3F2Qc6GFiUG4bcTZzrw7qmk654jhEAcBG",
+			"{converter}"
+		);
+	}
+}
+
+#[test]
+fn local_accounts_keep_their_address() {
+	let sender = CustomParaSender::get();
+	let location = Location::new(0, [AccountId32 { network: None, id: sender.clone().into() }]);
+	let account = Converter::CustomPara.convert(&location).unwrap();
+	assert_eq!(account, SovereignAccount(sender));
+	// Alice's well known address.
+	assert_eq!(
+		account.ss58(WESTEND_SS58_PREFIX),
+		"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+	);
+	assert_eq!(account.hex(), "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d");
+}
+
+#[test]
+fn converters_have_names() {
+	for converter in Converter::ALL {
+		assert_eq!(converter.to_string().parse(), Ok(converter));
+	}
+	assert!("moon-para".parse::<Converter>().is_err());
+}
@@ -0,0 +1,22 @@
+//! Dumps the JSON form of every constant of the lessons to the `fixtures` directory.
+//!
+//! ```sh
+//! cargo run -p fundamentals --features json --bin fixtures
+//! ```
+
+use std::{fs, path::Path, process::ExitCode};
+
+use fundamentals::json::{fixtures, FIXTURES_DIR};
+
+fn main() -> ExitCode {
+	for (file, value) in fixtures() {
+		let path = Path::new(FIXTURES_DIR).join(file);
+		let json = serde_json::to_string_pretty(&value).expect("JSON values can be printed");
+		if let Err(error) = fs::write(&path, json + "\n") {
+			eprintln!("could not write {}: {error}", path.display());
+			return ExitCode::FAILURE;
+		}
+		println!("wrote {}", path.display());
+	}
+	ExitCode::SUCCESS
+}
@@ -0,0 +1,50 @@
+//! Works out the location of a target as seen from a viewer.
+//!
+//! ```sh
+//! cargo run -p fundamentals --features location --bin locate -- PolkadotPara1000 KusamaPara1000
+//! cargo run -p fundamentals --features location --bin locate -- \
+//!     "GlobalConsensus(Polkadot)/Parachain(2004)" "GlobalConsensus(Polkadot)/Parachain(1000)"
+//! cargo run -p fundamentals --features location --bin locate -- --list
+//! ```
+
+use std::{env, process::ExitCode};
+
+use fundamentals::calculator::{parse, relative, universe};
+
+const USAGE: &str = "usage: locate <viewer> <target> | locate --list
+
+Both are universal locations: a point listed by `--list`, `Here` or junctions separated by `/`,
+like `GlobalConsensus(Polkadot)/Parachain(1000)`.";
+
+fn main() -> ExitCode {
+	let args: Vec<String> = env::args().skip(1).collect();
+	match &args[..] {
+		[list] if list == "--list" => {
+			for (name, location) in universe() {
+				println!("{name}: {location:?}");
+			}
+			ExitCode::SUCCESS
+		},
+		[viewer, target] => match locate(viewer, target) {
+			Ok(location) => {
+				println!("{location}");
+				ExitCode::SUCCESS
+			},
+			Err(error) => {
+				eprintln!("{error}");
+				ExitCode::FAILURE
+			},
+		},
+		_ => {
+			eprintln!("{USAGE}");
+			ExitCode::FAILURE
+		},
+	}
+}
+
+fn locate(viewer: &str, target: &str) -> Result<String, String> {
+	let viewer = parse(viewer)?;
+	let target = parse(target)?;
+	let location = relative(&viewer, &target).map_err(|error| format!("{error:?}"))?;
+	Ok(format!("{location:?}"))
+}
@@ -0,0 +1,177 @@
+//! # Location Calculator
+//!
+//! The locations in the first lesson are written by hand from several points of view.
+//! This module works them out instead.
+//!
+//! Every point of the lesson diagrams has a universal location: the junctions from the top of
+//! the absolute topography down to it, always starting with `GlobalConsensus`.
+//! Given the universal location of the viewer and of the target, we get the location of the
+//! target as the viewer sees it, the same way the executor does with `reanchored`.
+
+use crate::constants::ALICE;
+use xcm::latest::prelude::*;
+
+/// Why a location can't be worked out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CalculatorError {
+	/// Universal locations are `Here`, for the whole universe, or start with `GlobalConsensus`.
+	NotUniversal(InteriorLocation),
+	/// The result doesn't fit in a location.
+	TooLong,
+	/// The relative location goes up past the universe.
+	BeyondUniverse,
+}
+
+/// Whether `location` is a universal location.
+pub fn is_universal(location: &InteriorLocation) -> bool {
+	matches!(location.first(), None | Some(GlobalConsensus(_)))
+}
+
+/// The location of `target` as seen from `viewer`, both given by their universal location.
+pub fn relative(
+	viewer: &InteriorLocation,
+	target: &InteriorLocation,
+) -> Result<Location, CalculatorError> {
+	for location in [viewer, target] {
+		if !is_universal(location) {
+			return Err(CalculatorError::NotUniversal(location.clone()));
+		}
+	}
+	// Universal locations are relative to the universe, so that's our context.
+	Location::new(0, target.clone())
+		.reanchored(&Location::new(0, viewer.clone()), &Here)
+		.map_err(|_| CalculatorError::TooLong)
+}
+
+/// The universal location of what `viewer` sees at `location`.
+/// It undoes [`relative`].
+pub fn universal(
+	viewer: &InteriorLocation,
+	location: &Location,
+) -> Result<InteriorLocation, CalculatorError> {
+	if !is_universal(viewer) {
+		return Err(CalculatorError::NotUniversal(viewer.clone()));
+	}
+	if location.parent_count() as usize > viewer.len() {
+		return Err(CalculatorError::BeyondUniverse);
+	}
+	let universal = Location::new(0, viewer.clone())
+		.appended_with(location.clone())
+		.map_err(|_| CalculatorError::TooLong)?;
+	match universal.unpack() {
+		(0, _) if is_universal(universal.interior()) => Ok(universal.interior().clone()),
+		_ => Err(CalculatorError::NotUniversal(universal.interior().clone())),
+	}
+}
+
+/// Every point in the topography diagrams of the first lesson, with its universal location.
+pub fn universe() -> Vec<(&'static str, InteriorLocation)> {
+	let polkadot = GlobalConsensus(Polkadot);
+	let kusama = GlobalConsensus(Kusama);
+	let alice: [u8; 32] = ALICE.into();
+	vec![
+		// Relay A.
+		("PolkadotRelay", [polkadot].into()),
+		("PolkadotPara1000", [polkadot, Parachain(1000)].into()),
+		("PolkadotPara1000Alice", [polkadot, Parachain(1000), alice.into()].into()),
+		("PolkadotPara1000AssetsPallet", [polkadot, Parachain(1000), PalletInstance(50)].into()),
+		(
+			"PolkadotPara1000Asset1984",
+			[polkadot, Parachain(1000), PalletInstance(50), GeneralIndex(1984)].into(),
+		),
+		("PolkadotPara2004", [polkadot, Parachain(2004)].into()),
+		("PolkadotPara2004Bob", [polkadot, Parachain(2004), [0x22u8; 20].into()].into()),
+		("PolkadotPara2004EvmPallet", [polkadot, Parachain(2004), PalletInstance(51)].into()),
+		(
+			"PolkadotPara2004SmartContract",
+			[polkadot, Parachain(2004), PalletInstance(51), [0x55u8; 20].into()].into(),
+		),
+		// Relay B.
+		("KusamaRelay", [kusama].into()),
+		("KusamaPara1000", [kusama, Parachain(1000)].into()),
+		("KusamaPara1000NftPallet", [kusama, Parachain(1000), PalletInstance(52)].into()),
+		(
+			"KusamaPara1000Kitties",
+			[kusama, Parachain(1000), PalletInstance(52), GeneralIndex(21)].into(),
+		),
+		("KusamaUser1", [kusama, [0x11u8; 32].into()].into()),
+		(
+			"KusamaPlurality",
+			[kusama, Plurality { id: BodyId::Unit, part: BodyPart::Voice }].into(),
+		),
+		// Other consensus systems.
+		("Bitcoin", [GlobalConsensus(BitcoinCore)].into()),
+		("Ethereum", [GlobalConsensus(Ethereum { chain_id: 1 })].into()),
+	]
+}
+
+/// The universal location of the point called `name` in [`universe`].
+pub fn point(name: &str) -> Option<InteriorLocation> {
+	universe().into_iter().find(|(point, _)| *point == name).map(|(_, location)| location)
+}
+
+/// Reads a universal location.
+///
+/// It's either the name of a point in [`universe`], `Here`, or junctions separated by `/`,
+/// like `GlobalConsensus(Polkadot)/Parachain(1000)/AccountId32(0x0101...)`.
+pub fn parse(input: &str) -> Result<InteriorLocation, String> {
+	let input = input.trim();
+	if let Some(location) = point(input) {
+		return Ok(location);
+	}
+	if input.is_empty() || input == "Here" {
+		return Ok(Here);
+	}
+	let mut location = Here;
+	for junction in input.split('/') {
+		location.push(parse_junction(junction.trim())?).map_err(|_| "too many junctions")?;
+	}
+	Ok(location)
+}
+
+fn parse_junction(input: &str) -> Result<Junction, String> {
+	let (name, argument) = input
+		.strip_suffix(')')
+		.and_then(|input| input.split_once('('))
+		.ok_or_else(|| format!("expected a junction like `Parachain(1000)`, got `{input}`"))?;
+	let junction = match name {
+		"GlobalConsensus" => GlobalConsensus(parse_network(argument)?),
+		"Parachain" => Parachain(number(argument)?),
+		"PalletInstance" => PalletInstance(number(argument)?),
+		"GeneralIndex" => GeneralIndex(number(argument)?),
+		"AccountId32" => AccountId32 { network: None, id: bytes(argument)? },
+		"AccountKey20" => AccountKey20 { network: None, key: bytes(argument)? },
+		other => return Err(format!("unknown junction `{other}`")),
+	};
+	Ok(junction)
+}
+
+fn parse_network(input: &str) -> Result<NetworkId, String> {
+	match input {
+		"Polkadot" => Ok(Polkadot),
+		"Kusama" => Ok(Kusama),
+		"Bitcoin" | "BitcoinCore" => Ok(BitcoinCore),
+		"Ethereum" => Ok(Ethereum { chain_id: 1 }),
+		other => Err(format!("unknown network `{other}`")),
+	}
+}
+
+fn number<T: TryFrom<u128>>(input: &str) -> Result<T, String> {
+	input
+		.parse::<u128>()
+		.ok()
+		.and_then(|number| T::try_from(number).ok())
+		.ok_or_else(|| format!("`{input}` is not a number or is too big"))
+}
+
+fn bytes<const N: usize>(input: &str) -> Result<[u8; N], String> {
+	let input = input.strip_prefix("0x").unwrap_or(input);
+	if !input.is_ascii() || input.len() != 2 * N {
+		return Err(format!("expected {N} bytes of hex, got `{input}`"));
+	}
+	(0..N)
+		.map(|i| u8::from_str_radix(&input[2 * i..2 * i + 2], 16))
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|_| format!("`{input}` is not hex"))
+		.map(|bytes| bytes.try_into().expect("there are N bytes"))
+}
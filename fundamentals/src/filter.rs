@@ -0,0 +1,100 @@
+//! # Asset Filters
+//!
+//! Instructions like `DepositAsset` don't take assets but a filter, which picks assets out of the
+//! holding register. This module applies a filter to a set of assets, the way the executor does
+//! for `DepositAsset`, so we can see what each kind of filter selects.
+//!
+//! Chains limit how many different assets can be in holding with `MaxAssetsIntoHolding`.
+//! Uncounted wildcards are weighed as if they took that many assets.
+
+use xcm::latest::prelude::*;
+
+/// The `MaxAssetsIntoHolding` of most chains.
+pub const MAX_ASSETS_INTO_HOLDING: u32 = 64;
+
+/// Why a filter can't be applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FilterError {
+	/// There are more different assets than the holding register can take.
+	HoldingWouldOverflow { assets: usize, limit: u32 },
+}
+
+/// The assets a filter picked, and the ones it left behind.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selection {
+	pub selected: Assets,
+	pub remaining: Assets,
+}
+
+/// Applies `filter` to `holding`, which can have at most `limit` different assets.
+///
+/// Like `DepositAsset`, asking for more than what's there isn't an error: a `Definite` filter
+/// selects what it can of each asset, and counted wildcards stop after `count` assets, in the
+/// order of `holding`.
+pub fn apply(filter: &AssetFilter, holding: &Assets, limit: u32) -> Result<Selection, FilterError> {
+	if holding.len() > limit as usize {
+		return Err(FilterError::HoldingWouldOverflow { assets: holding.len(), limit });
+	}
+	let mut selected = Vec::new();
+	let mut remaining = Vec::new();
+	match filter {
+		Definite(wanted) =>
+			for asset in holding.inner() {
+				let (taken, left) = take_definite(wanted, asset);
+				selected.extend(taken);
+				remaining.extend(left);
+			},
+		Wild(wild) => {
+			let mut count = match wild {
+				AllCounted(count) | AllOfCounted { count, .. } => *count,
+				All | AllOf { .. } => u32::MAX,
+			};
+			for asset in holding.inner() {
+				if count > 0 && wild_matches(wild, asset) {
+					count -= 1;
+					selected.push(asset.clone());
+				} else {
+					remaining.push(asset.clone());
+				}
+			}
+		},
+	}
+	Ok(Selection { selected: selected.into(), remaining: remaining.into() })
+}
+
+/// Splits `asset` into the part `wanted` asks for and the rest.
+fn take_definite(wanted: &Assets, asset: &Asset) -> (Option<Asset>, Option<Asset>) {
+	match asset.fun {
+		Fungible(amount) => {
+			let asked = wanted
+				.inner()
+				.iter()
+				.filter(|want| want.id == asset.id)
+				.find_map(|want| match want.fun {
+					Fungible(asked) => Some(asked),
+					NonFungible(_) => None,
+				})
+				.unwrap_or(0);
+			let taken = asked.min(amount);
+			let part = |amount: u128| (amount > 0).then(|| Asset::from((asset.id.clone(), amount)));
+			(part(taken), part(amount - taken))
+		},
+		NonFungible(_) => match wanted.contains(asset) {
+			true => (Some(asset.clone()), None),
+			false => (None, Some(asset.clone())),
+		},
+	}
+}
+
+/// Whether a wildcard picks `asset`, regardless of its count.
+fn wild_matches(wild: &WildAsset, asset: &Asset) -> bool {
+	match wild {
+		All | AllCounted(_) => true,
+		AllOf { id, fun } | AllOfCounted { id, fun, .. } =>
+			*id == asset.id &&
+				matches!(
+					(fun, &asset.fun),
+					(WildFungible, Fungible(_)) | (WildNonFungible, NonFungible(_))
+				),
+	}
+}
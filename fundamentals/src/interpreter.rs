@@ -0,0 +1,173 @@
+//! # XCM Interpreter
+//!
+//! The lessons check programs by comparing them with the expected instructions.
+//! This module runs them instead, on a small in-memory ledger, so we can see what each
+//! instruction does to the registers of the executor.
+//!
+//! Only a handful of v5 instructions are supported, the ones of the lessons and a few more.
+//! The interpreter doesn't weigh programs: the fees register keeps whatever `PayFees` puts in it.
+//! A real executor charges the weight of the program from it and can give the rest back with
+//! `RefundSurplus`.
+
+use std::collections::BTreeMap;
+
+use xcm::latest::prelude::*;
+use xcm_executor::AssetsInHolding;
+
+/// The fungible balances of every account, by their location.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ledger {
+	balances: BTreeMap<Location, BTreeMap<AssetId, u128>>,
+}
+
+impl Ledger {
+	/// How much of `id` the account at `who` has.
+	pub fn balance(&self, who: &Location, id: &AssetId) -> u128 {
+		self.balances.get(who).and_then(|assets| assets.get(id)).copied().unwrap_or(0)
+	}
+
+	/// Adds `asset` to the account at `who`.
+	pub fn deposit(&mut self, who: &Location, asset: &Asset) -> Result<(), XcmError> {
+		let Fungible(amount) = asset.fun else { return Err(XcmError::Unimplemented) };
+		let assets = self.balances.entry(who.clone()).or_default();
+		let balance = assets.entry(asset.id.clone()).or_default();
+		*balance = balance.checked_add(amount).ok_or(XcmError::Overflow)?;
+		Ok(())
+	}
+
+	/// Takes `asset` from the account at `who`.
+	pub fn withdraw(&mut self, who: &Location, asset: &Asset) -> Result<(), XcmError> {
+		let Fungible(amount) = asset.fun else { return Err(XcmError::Unimplemented) };
+		let balance = self
+			.balances
+			.get_mut(who)
+			.and_then(|assets| assets.get_mut(&asset.id))
+			.ok_or(XcmError::NotWithdrawable)?;
+		*balance = balance.checked_sub(amount).ok_or(XcmError::NotWithdrawable)?;
+		Ok(())
+	}
+}
+
+/// The registers of the interpreter after running an instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+	/// The index of the instruction in the program.
+	pub index: usize,
+	pub holding: AssetsInHolding,
+	pub fees: AssetsInHolding,
+	pub origin: Option<Location>,
+	/// The error of the instruction, which stops the program.
+	pub error: Option<XcmError>,
+}
+
+/// Runs XCM programs on a [`Ledger`].
+#[derive(Clone, Debug)]
+pub struct Interpreter {
+	ledger: Ledger,
+	origin: Option<Location>,
+	holding: AssetsInHolding,
+	fees: AssetsInHolding,
+	error: Option<XcmError>,
+}
+
+impl Interpreter {
+	/// An interpreter for programs sent by `origin`, with empty registers.
+	pub fn new(ledger: Ledger, origin: impl Into<Location>) -> Self {
+		Self {
+			ledger,
+			origin: Some(origin.into()),
+			holding: AssetsInHolding::new(),
+			fees: AssetsInHolding::new(),
+			error: None,
+		}
+	}
+
+	pub fn ledger(&self) -> &Ledger {
+		&self.ledger
+	}
+
+	pub fn holding(&self) -> &AssetsInHolding {
+		&self.holding
+	}
+
+	pub fn fees(&self) -> &AssetsInHolding {
+		&self.fees
+	}
+
+	pub fn origin(&self) -> Option<&Location> {
+		self.origin.as_ref()
+	}
+
+	pub fn error(&self) -> Option<XcmError> {
+		self.error
+	}
+
+	/// Runs `xcm` one instruction at a time, and returns the registers after each of them.
+	///
+	/// The program stops at the first error. Whatever is left in holding stays there, where a
+	/// real executor would trap it.
+	pub fn execute<Call>(&mut self, xcm: &Xcm<Call>) -> Vec<Step> {
+		let mut steps = Vec::new();
+		for (index, instruction) in xcm.inner().iter().enumerate() {
+			self.error = self.process(instruction).err();
+			steps.push(Step {
+				index,
+				holding: self.holding.clone(),
+				fees: self.fees.clone(),
+				origin: self.origin.clone(),
+				error: self.error,
+			});
+			if self.error.is_some() {
+				break;
+			}
+		}
+		steps
+	}
+
+	fn process<Call>(&mut self, instruction: &Instruction<Call>) -> Result<(), XcmError> {
+		match instruction {
+			WithdrawAsset(assets) => {
+				let origin = self.origin.as_ref().ok_or(XcmError::BadOrigin)?;
+				// Either every asset is withdrawn or none is.
+				let mut ledger = self.ledger.clone();
+				for asset in assets.inner() {
+					ledger.withdraw(origin, asset)?;
+				}
+				self.ledger = ledger;
+				assets.inner().iter().cloned().for_each(|asset| self.holding.subsume(asset));
+			},
+			PayFees { asset } => {
+				let fees = self
+					.holding
+					.try_take(Definite(asset.clone().into()))
+					.map_err(|_| XcmError::NotHoldingFees)?;
+				self.fees.subsume_assets(fees);
+			},
+			DepositAsset { assets, beneficiary } => {
+				// Either every asset is deposited or none is, and holding is left alone.
+				let mut ledger = self.ledger.clone();
+				for asset in self.holding.min(assets).assets_iter() {
+					ledger.deposit(beneficiary, &asset)?;
+				}
+				self.ledger = ledger;
+				let _ = self.holding.saturating_take(assets.clone());
+			},
+			BurnAsset(assets) => {
+				let _ = self.holding.saturating_take(Definite(assets.clone()));
+			},
+			ExpectAsset(assets) =>
+				self.holding.ensure_contains(assets).map_err(|_| XcmError::ExpectationFalse)?,
+			ClearOrigin => self.origin = None,
+			DescendOrigin(interior) => {
+				let origin = self.origin.as_mut().ok_or(XcmError::BadOrigin)?;
+				origin.append_with(interior.clone()).map_err(|_| XcmError::LocationFull)?;
+			},
+			ExpectOrigin(origin) =>
+				if *origin != self.origin {
+					return Err(XcmError::ExpectationFalse);
+				},
+			_ => return Err(XcmError::Unimplemented),
+		}
+		Ok(())
+	}
+}
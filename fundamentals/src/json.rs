@@ -0,0 +1,226 @@
+//! # JSON Form
+//!
+//! The locations, assets, filters and programs of the lessons as JSON, for front-ends.
+//!
+//! The JSON follows the Rust types, as described by `scale-json`, and is tagged with the version:
+//! `{ "V5": { "parents": 1, "interior": "Here" } }`. It's the form `xcm-codec` reads and writes.
+//! Locations, assets and programs can be read back from older versions too, and are converted
+//! to the latest one. Asset ids and filters have no versioned type in `xcm`, so they're only
+//! written and read as `V5`.
+//!
+//! Every constant of the lessons is dumped to the `fixtures` directory by the `fixtures` binary.
+//! A test checks the files are up to date.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use scale_json::Error;
+use serde_json::{Map, Value};
+use xcm::{latest::prelude::*, VersionedAsset, VersionedAssets, VersionedLocation, VersionedXcm};
+
+use crate::{asset, instruction, location};
+
+/// Types with a JSON form.
+pub trait Json: Sized {
+	fn to_json(&self) -> Value;
+	fn from_json(value: &Value) -> Result<Self, Error>;
+}
+
+/// The JSON form of an XCM type, which the JSON form always covers.
+fn xcm_to_json<T: Encode + TypeInfo + 'static>(value: &T) -> Value {
+	scale_json::to_json(value).expect("the JSON form covers every XCM type")
+}
+
+/// Writes `T` through its versioned type, and reads any version of it back as the latest one.
+fn versioned_to_json<T, Versioned>(value: &T) -> Value
+where
+	T: Clone,
+	Versioned: From<T> + Encode + TypeInfo + 'static,
+{
+	xcm_to_json(&Versioned::from(value.clone()))
+}
+
+fn versioned_from_json<T, Versioned>(value: &Value) -> Result<T, Error>
+where
+	Versioned: TryInto<T> + Decode + TypeInfo + 'static,
+{
+	scale_json::from_json::<Versioned>(value)?
+		.try_into()
+		.map_err(|_| Error::Json("the value has no equivalent in the latest version".into()))
+}
+
+impl Json for Location {
+	fn to_json(&self) -> Value {
+		versioned_to_json::<_, VersionedLocation>(self)
+	}
+
+	fn from_json(value: &Value) -> Result<Self, Error> {
+		versioned_from_json::<_, VersionedLocation>(value)
+	}
+}
+
+impl Json for Asset {
+	fn to_json(&self) -> Value {
+		versioned_to_json::<_, VersionedAsset>(self)
+	}
+
+	fn from_json(value: &Value) -> Result<Self, Error> {
+		versioned_from_json::<_, VersionedAsset>(value)
+	}
+}
+
+impl Json for Assets {
+	fn to_json(&self) -> Value {
+		versioned_to_json::<_, VersionedAssets>(self)
+	}
+
+	fn from_json(value: &Value) -> Result<Self, Error> {
+		versioned_from_json::<_, VersionedAssets>(value)
+	}
+}
+
+impl Json for Xcm<()> {
+	fn to_json(&self) -> Value {
+		versioned_to_json::<_, VersionedXcm<()>>(self)
+	}
+
+	fn from_json(value: &Value) -> Result<Self, Error> {
+		versioned_from_json::<_, VersionedXcm<()>>(value)
+	}
+}
+
+/// Tags the types without a versioned type in `xcm` the same way.
+#[derive(Encode, Decode, TypeInfo)]
+enum Latest<T> {
+	#[codec(index = 5)]
+	V5(T),
+}
+
+impl Json for AssetId {
+	fn to_json(&self) -> Value {
+		xcm_to_json(&Latest::V5(self.clone()))
+	}
+
+	fn from_json(value: &Value) -> Result<Self, Error> {
+		scale_json::from_json(value).map(|Latest::V5(id)| id)
+	}
+}
+
+impl Json for AssetFilter {
+	fn to_json(&self) -> Value {
+		xcm_to_json(&Latest::V5(self.clone()))
+	}
+
+	fn from_json(value: &Value) -> Result<Self, Error> {
+		scale_json::from_json(value).map(|Latest::V5(filter)| filter)
+	}
+}
+
+/// Account bytes don't change between versions, they're a hex string.
+impl Json for [u8; 32] {
+	fn to_json(&self) -> Value {
+		xcm_to_json(self)
+	}
+
+	fn from_json(value: &Value) -> Result<Self, Error> {
+		scale_json::from_json(value)
+	}
+}
+
+/// Where the fixtures are kept.
+pub const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+/// Adds the JSON form of each constant of `module`, named after it with `prefix` in front.
+macro_rules! constants {
+	($fixtures:ident, $prefix:literal, $($module:ident)::+, [$($constant:ident),* $(,)?]) => {
+		$(
+			$fixtures.insert(
+				concat!($prefix, stringify!($constant)).into(),
+				$($module)::+::$constant::get().to_json(),
+			);
+		)*
+	};
+}
+
+/// The contents of each file in the `fixtures` directory, by file name.
+pub fn fixtures() -> Vec<(&'static str, Value)> {
+	let mut locations = Map::new();
+	constants!(
+		locations,
+		"relative_to_polkadot_relay::",
+		location::relative_to_polkadot_relay,
+		[
+			PolkadotPara1000,
+			PolkadotPara2004,
+			PolkadotRelay,
+			AliceBytes,
+			PolkadotPara1000Alice,
+			PolkadotPara1000AssetsPallet,
+			PolkadotPara1000Asset1984,
+			KusamaPara1000,
+		]
+	);
+	constants!(
+		locations,
+		"relative_to_polkadot_para_1000::",
+		location::relative_to_polkadot_para_1000,
+		[
+			PolkadotPara1000,
+			PolkadotPara2004,
+			PolkadotRelay,
+			AliceBytes,
+			PolkadotPara1000Alice,
+			PolkadotPara1000AssetsPallet,
+			PolkadotPara1000Asset1984,
+			KusamaPara1000,
+		]
+	);
+	constants!(
+		locations,
+		"absolute::",
+		location::absolute,
+		[
+			PolkadotPara1000,
+			PolkadotPara2004,
+			PolkadotRelay,
+			AliceBytes,
+			PolkadotPara1000Alice,
+			PolkadotPara1000AssetsPallet,
+			PolkadotPara1000Asset1984,
+			KusamaPara1000,
+		]
+	);
+
+	let mut assets = Map::new();
+	constants!(
+		assets,
+		"",
+		asset,
+		[
+			EmptyAssets,
+			Usdt,
+			DotToken,
+			OneHundredUsdt,
+			OneHundredDot,
+			NftLocation,
+			Nft,
+			AllAssetsFilter,
+			DotFilter,
+			UsdtFilter,
+		]
+	);
+
+	let programs = [
+		("clear_origin_message", instruction::clear_origin_message()),
+		("withdraw_asset", instruction::withdraw_asset()),
+		("withdraw_and_deposit", instruction::withdraw_and_deposit()),
+		("withdraw_and_deposit_paying_fees", instruction::withdraw_and_deposit_paying_fees()),
+	];
+	let programs: Map<_, _> =
+		programs.into_iter().map(|(name, xcm)| (name.to_string(), xcm.to_json())).collect();
+
+	vec![
+		("location.json", Value::Object(locations)),
+		("asset.json", Value::Object(assets)),
+		("instruction.json", Value::Object(programs)),
+	]
+}
@@ -2,14 +2,38 @@
 #[cfg(feature = "location")]
 pub mod location;
 
+/// Works out the locations of lesson 1 from any point of view.
+#[cfg(feature = "location")]
+pub mod calculator;
+
 /// Lesson 2
 #[cfg(feature = "asset")]
 pub mod asset;
 
+/// Shows what the filters of lesson 2 pick out of a set of assets.
+#[cfg(feature = "asset")]
+pub mod filter;
+
 /// Lesson 3
 #[cfg(feature = "instruction")]
 pub mod instruction;
 
+/// Lesson 5
+#[cfg(feature = "versions")]
+pub mod versions;
+
+/// A linter for common mistakes in XCM programs.
+#[cfg(feature = "lint")]
+pub mod lint;
+
+/// Runs the programs of lesson 4 on an in-memory ledger.
+#[cfg(feature = "interpreter")]
+pub mod interpreter;
+
+/// The constants and programs of the lessons as JSON.
+#[cfg(feature = "json")]
+pub mod json;
+
 /// Constants for accounts.
 mod constants;
 
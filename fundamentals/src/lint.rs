@@ -0,0 +1,276 @@
+//! # XCM Linter
+//!
+//! A static checker for the classic mistakes in XCM programs.
+//! It walks a program without executing it, following nested programs like the `remote_xcm`
+//! of `InitiateTransfer`, and keeps a rough model of the holding register, the origin and
+//! whether fees were paid.
+//!
+//! Each finding is a [`Diagnostic`] pointing at the offending instruction.
+
+use core::fmt;
+
+use xcm::latest::prelude::*;
+use xcm_executor::AssetsInHolding;
+
+/// The catalog of rules checked by the linter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+	/// An asset filter uses `All` or `AllOf` instead of their counted variants.
+	UncountedWildcard,
+	/// A `Transact` is executed after the origin was cleared.
+	TransactWithoutOrigin,
+	/// A program which has to pay for its execution never does.
+	MissingFees,
+	/// `PayFees` or `BuyExecution` use assets which are not in the holding register.
+	FeesNotInHolding,
+}
+
+impl Rule {
+	/// Every rule in the catalog.
+	pub const ALL: [Rule; 4] = [
+		Rule::UncountedWildcard,
+		Rule::TransactWithoutOrigin,
+		Rule::MissingFees,
+		Rule::FeesNotInHolding,
+	];
+
+	/// A short explanation of the mistake.
+	pub fn description(&self) -> &'static str {
+		match self {
+			Rule::UncountedWildcard =>
+				"use `AllCounted` or `AllOfCounted`, uncounted wildcards are weighed for the \
+				 maximum number of assets in holding",
+			Rule::TransactWithoutOrigin =>
+				"`Transact` needs an origin, but it was cleared by a previous instruction",
+			Rule::MissingFees =>
+				"the program has to pay for its execution with `PayFees` or `BuyExecution` \
+				 before doing any work",
+			Rule::FeesNotInHolding => "the fees are not in the holding register",
+		}
+	}
+}
+
+/// A broken rule, found at `path`.
+///
+/// The path holds the index of the instruction in the program. When the instruction is part of
+/// a nested program, the index of the instruction holding that program comes first.
+/// An empty path points to the whole top-level program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+	pub rule: Rule,
+	pub path: Vec<usize>,
+}
+
+impl Diagnostic {
+	pub fn new(rule: Rule, path: impl Into<Vec<usize>>) -> Self {
+		Self { rule, path: path.into() }
+	}
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let path: Vec<String> = self.path.iter().map(|index| index.to_string()).collect();
+		write!(f, "[{}] {:?}: {}", path.join("."), self.rule, self.rule.description())
+	}
+}
+
+/// Lints a program sent to a chain, which has to pay for its own execution.
+pub fn lint<Call>(xcm: &Xcm<Call>) -> Vec<Diagnostic> {
+	let mut linter = Linter::default();
+	linter.program(xcm, State::paying(), &mut Vec::new());
+	linter.diagnostics
+}
+
+/// Lints a program executed locally, for example with `pallet_xcm::execute`.
+/// The weight is paid by the extrinsic, so no fees are needed on the top level,
+/// but nested programs sent to other chains still have to pay.
+pub fn lint_local<Call>(xcm: &Xcm<Call>) -> Vec<Diagnostic> {
+	let mut linter = Linter::default();
+	linter.program(xcm, State::paid(), &mut Vec::new());
+	linter.diagnostics
+}
+
+/// What the linter knows about the executor while walking a program.
+#[derive(Clone)]
+struct State {
+	/// The holding register, `None` when we can't tell what's in it.
+	holding: Option<AssetsInHolding>,
+	origin_cleared: bool,
+	fees_paid: bool,
+}
+
+impl State {
+	fn paying() -> Self {
+		Self { holding: Some(AssetsInHolding::new()), origin_cleared: false, fees_paid: false }
+	}
+
+	fn paid() -> Self {
+		Self { fees_paid: true, ..Self::paying() }
+	}
+
+	/// The state of a program sent to another chain carrying `assets`.
+	/// The executor puts the assets in holding and clears the origin before running it.
+	fn remote<'a>(
+		assets: impl IntoIterator<Item = &'a AssetFilter>,
+		origin_cleared: bool,
+		fees_paid: bool,
+	) -> Self {
+		let mut state = Self { holding: Some(AssetsInHolding::new()), origin_cleared, fees_paid };
+		for filter in assets {
+			match filter {
+				Definite(assets) => state.deposit(assets),
+				Wild(_) => state.holding = None,
+			}
+		}
+		state
+	}
+
+	fn deposit(&mut self, assets: &Assets) {
+		if let Some(holding) = self.holding.as_mut() {
+			assets.inner().iter().cloned().for_each(|asset| holding.subsume(asset));
+		}
+	}
+
+	fn take(&mut self, filter: &AssetFilter) {
+		if let Some(holding) = self.holding.as_mut() {
+			let _ = holding.saturating_take(filter.clone());
+		}
+	}
+}
+
+#[derive(Default)]
+struct Linter {
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter {
+	fn report(&mut self, rule: Rule, path: &[usize]) {
+		self.diagnostics.push(Diagnostic::new(rule, path));
+	}
+
+	/// Walks `xcm` starting from `state` and returns the state after its last instruction.
+	fn program<Call>(&mut self, xcm: &Xcm<Call>, mut state: State, path: &mut Vec<usize>) -> State {
+		for (index, instruction) in xcm.inner().iter().enumerate() {
+			path.push(index);
+			self.instruction(instruction, &mut state, path);
+			path.pop();
+		}
+		if !state.fees_paid {
+			self.report(Rule::MissingFees, path);
+		}
+		state
+	}
+
+	fn instruction<Call>(
+		&mut self,
+		instruction: &Instruction<Call>,
+		state: &mut State,
+		path: &mut Vec<usize>,
+	) {
+		if !state.fees_paid && !allowed_before_fees(instruction) {
+			self.report(Rule::MissingFees, path);
+			// Only report the first one.
+			state.fees_paid = true;
+		}
+
+		match instruction {
+			WithdrawAsset(assets) | ReserveAssetDeposited(assets) | ReceiveTeleportedAsset(assets) |
+			ClaimAsset { assets, .. } => state.deposit(assets),
+			BurnAsset(assets) => state.take(&Definite(assets.clone())),
+			PayFees { asset: fees } | BuyExecution { fees, .. } => {
+				self.fees(fees, state, path);
+				state.fees_paid = true;
+			},
+			UnpaidExecution { .. } => state.fees_paid = true,
+			// The unused fees go back to holding, we don't know how many.
+			RefundSurplus => state.holding = None,
+			ClearOrigin => state.origin_cleared = true,
+			Transact { .. } =>
+				if state.origin_cleared {
+					self.report(Rule::TransactWithoutOrigin, path);
+				},
+			DepositAsset { assets, .. } => {
+				self.filter(assets, path);
+				state.take(assets);
+			},
+			ExchangeAsset { give, .. } => {
+				self.filter(give, path);
+				state.holding = None;
+			},
+			ReportHolding { assets, .. } => self.filter(assets, path),
+			DepositReserveAsset { assets, xcm, .. } |
+			InitiateReserveWithdraw { assets, xcm, .. } |
+			InitiateTeleport { assets, xcm, .. } => {
+				self.filter(assets, path);
+				state.take(assets);
+				self.program(xcm, State::remote([assets], true, false), path);
+			},
+			TransferReserveAsset { assets, xcm, .. } => {
+				let assets = Definite(assets.clone());
+				self.program(xcm, State::remote([&assets], true, false), path);
+			},
+			InitiateTransfer { remote_fees, preserve_origin, assets, remote_xcm, .. } => {
+				let filters: Vec<&AssetFilter> =
+					assets.iter().map(|filter| filter.inner()).collect();
+				if let Some(fees) = remote_fees {
+					self.filter(fees.inner(), path);
+					state.take(fees.inner());
+				}
+				for filter in &filters {
+					self.filter(filter, path);
+					state.take(filter);
+				}
+				let remote = State::remote(filters, !preserve_origin, remote_fees.is_some());
+				self.program(remote_xcm, remote, path);
+			},
+			ExecuteWithOrigin { descendant_origin, xcm } => {
+				let origin_cleared = state.origin_cleared;
+				let mut inner = state.clone();
+				inner.origin_cleared |= descendant_origin.is_none();
+				// The inner program shares our holding and fees.
+				inner.fees_paid = true;
+				let inner = self.program(xcm, inner, path);
+				state.holding = inner.holding;
+				state.origin_cleared = origin_cleared;
+			},
+			// These run after the rest of the program, when the holding could be anything.
+			SetErrorHandler(xcm) | SetAppendix(xcm) => {
+				let later = State { holding: None, fees_paid: true, ..state.clone() };
+				self.program(xcm, later, path);
+			},
+			_ => {},
+		}
+	}
+
+	fn filter(&mut self, filter: &AssetFilter, path: &[usize]) {
+		if let Wild(All | AllOf { .. }) = filter {
+			self.report(Rule::UncountedWildcard, path);
+		}
+	}
+
+	fn fees(&mut self, fees: &Asset, state: &mut State, path: &[usize]) {
+		let Some(holding) = state.holding.as_mut() else { return };
+		if holding.ensure_contains(&fees.clone().into()).is_err() {
+			self.report(Rule::FeesNotInHolding, path);
+		}
+		let _ = holding.saturating_take(Definite(fees.clone().into()));
+	}
+}
+
+/// The instructions the barrier accepts before the fees are paid.
+fn allowed_before_fees<Call>(instruction: &Instruction<Call>) -> bool {
+	matches!(
+		instruction,
+		WithdrawAsset(..) |
+			ReserveAssetDeposited(..) |
+			ReceiveTeleportedAsset(..) |
+			ClaimAsset { .. } |
+			ClearOrigin |
+			DescendOrigin(..) |
+			AliasOrigin(..) |
+			SetHints { .. } |
+			PayFees { .. } |
+			BuyExecution { .. } |
+			UnpaidExecution { .. }
+	)
+}
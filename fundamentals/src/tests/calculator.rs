@@ -0,0 +1,140 @@
+use crate::{calculator::*, location::*};
+use xcm::latest::prelude::*;
+
+/// The constants of a `relative_to_*` module, by the name of their point in the universe.
+fn lesson_constants(
+	polkadot_para_1000: Location,
+	polkadot_para_2004: Location,
+	polkadot_relay: Location,
+	polkadot_para_1000_alice: Location,
+	polkadot_para_1000_assets_pallet: Location,
+	polkadot_para_1000_asset_1984: Location,
+	kusama_para_1000: Location,
+) -> Vec<(&'static str, Location)> {
+	vec![
+		("PolkadotPara1000", polkadot_para_1000),
+		("PolkadotPara2004", polkadot_para_2004),
+		("PolkadotRelay", polkadot_relay),
+		("PolkadotPara1000Alice", polkadot_para_1000_alice),
+		("PolkadotPara1000AssetsPallet", polkadot_para_1000_assets_pallet),
+		("PolkadotPara1000Asset1984", polkadot_para_1000_asset_1984),
+		("KusamaPara1000", kusama_para_1000),
+	]
+}
+
+fn assert_seen_from(viewer: InteriorLocation, constants: Vec<(&'static str, Location)>) {
+	for (name, expected) in constants {
+		let target = point(name).unwrap();
+		assert_eq!(relative(&viewer, &target), Ok(expected), "{name}");
+	}
+}
+
+#[test]
+fn regenerates_relative_to_polkadot_relay() {
+	use relative_to_polkadot_relay::*;
+	let constants = lesson_constants(
+		PolkadotPara1000::get(),
+		PolkadotPara2004::get(),
+		PolkadotRelay::get(),
+		PolkadotPara1000Alice::get(),
+		PolkadotPara1000AssetsPallet::get(),
+		PolkadotPara1000Asset1984::get(),
+		KusamaPara1000::get(),
+	);
+	assert_seen_from(point("PolkadotRelay").unwrap(), constants);
+}
+
+#[test]
+fn regenerates_relative_to_polkadot_para_1000() {
+	use relative_to_polkadot_para_1000::*;
+	let constants = lesson_constants(
+		PolkadotPara1000::get(),
+		PolkadotPara2004::get(),
+		PolkadotRelay::get(),
+		PolkadotPara1000Alice::get(),
+		PolkadotPara1000AssetsPallet::get(),
+		PolkadotPara1000Asset1984::get(),
+		KusamaPara1000::get(),
+	);
+	assert_seen_from(point("PolkadotPara1000").unwrap(), constants);
+}
+
+#[test]
+fn regenerates_absolute() {
+	use absolute::*;
+	let constants = lesson_constants(
+		PolkadotPara1000::get(),
+		PolkadotPara2004::get(),
+		PolkadotRelay::get(),
+		PolkadotPara1000Alice::get(),
+		PolkadotPara1000AssetsPallet::get(),
+		PolkadotPara1000Asset1984::get(),
+		KusamaPara1000::get(),
+	);
+	// The universe sees every point at its universal location.
+	assert_seen_from(Here, constants);
+}
+
+#[test]
+fn from_a_smart_contract_and_kusama() {
+	let contract = point("PolkadotPara2004SmartContract").unwrap();
+	let kusama = point("KusamaRelay").unwrap();
+	let cases = [
+		(&contract, "PolkadotPara2004", Location::new(2, [])),
+		(
+			&contract,
+			"PolkadotPara2004Bob",
+			Location::new(2, [AccountKey20 { network: None, key: [0x22; 20] }]),
+		),
+		(&contract, "PolkadotPara1000", Location::new(3, [Parachain(1000)])),
+		(
+			&contract,
+			"KusamaPara1000Kitties",
+			Location::new(
+				4,
+				[GlobalConsensus(Kusama), Parachain(1000), PalletInstance(52), GeneralIndex(21)],
+			),
+		),
+		(&kusama, "KusamaUser1", Location::new(0, [AccountId32 { network: None, id: [0x11; 32] }])),
+		(&kusama, "PolkadotRelay", Location::new(1, [GlobalConsensus(Polkadot)])),
+		(&kusama, "Ethereum", Location::new(1, [GlobalConsensus(Ethereum { chain_id: 1 })])),
+	];
+
+	for (viewer, name, expected) in cases {
+		assert_eq!(relative(viewer, &point(name).unwrap()), Ok(expected), "{name}");
+	}
+}
+
+#[test]
+fn universal_undoes_relative() {
+	for (_, viewer) in universe() {
+		for (name, target) in universe() {
+			let location = relative(&viewer, &target).unwrap();
+			assert_eq!(universal(&viewer, &location), Ok(target), "{name} from {viewer:?}");
+		}
+	}
+	let relay = point("PolkadotRelay").unwrap();
+	assert_eq!(universal(&relay, &Location::new(2, [])), Err(CalculatorError::BeyondUniverse));
+}
+
+#[test]
+fn parses_universal_locations() {
+	assert_eq!(parse("Here"), Ok(Here));
+	assert_eq!(parse("PolkadotPara1000"), Ok(point("PolkadotPara1000").unwrap()));
+	assert_eq!(
+		parse("GlobalConsensus(Polkadot)/Parachain(1000)/PalletInstance(50)/GeneralIndex(1984)"),
+		Ok(point("PolkadotPara1000Asset1984").unwrap())
+	);
+	assert_eq!(
+		parse(&format!("GlobalConsensus(Kusama)/AccountId32(0x{})", "11".repeat(32))),
+		Ok(point("KusamaUser1").unwrap())
+	);
+	assert!(parse("Parachain(1000)/Spaceship(1)").is_err());
+	assert!(parse("Parachain(99999999999)").is_err());
+
+	let not_universal = parse("Parachain(1000)").unwrap();
+	assert_eq!(
+		relative(&Here, &not_universal),
+		Err(CalculatorError::NotUniversal(not_universal.clone()))
+	);
+}
@@ -0,0 +1,113 @@
+use crate::{asset::*, filter::*};
+use xcm::latest::prelude::*;
+
+fn nft(instance: u64) -> Asset {
+	(NftLocation::get(), instance).into()
+}
+
+fn usdt(amount: u128) -> Asset {
+	(Usdt::get(), amount).into()
+}
+
+fn dot(amount: u128) -> Asset {
+	(DotToken::get(), amount).into()
+}
+
+/// Sorted like `Assets`: USDT and the NFTs live on AssetHub, DOT is up in the relay chain.
+fn holding() -> Assets {
+	vec![usdt(50), nft(69), nft(70), dot(100)].into()
+}
+
+fn assets(assets: &[Asset]) -> Assets {
+	assets.to_vec().into()
+}
+
+#[test]
+fn what_each_filter_selects() {
+	let usdt_id = Usdt::get();
+	let nft_id = AssetId(NftLocation::get());
+	// (filter, selected, remaining)
+	let table: Vec<(AssetFilter, Vec<Asset>, Vec<Asset>)> = vec![
+		// Wildcards.
+		(AllAssetsFilter::get(), vec![usdt(50), nft(69), nft(70), dot(100)], vec![]),
+		(Wild(AllCounted(0)), vec![], vec![usdt(50), nft(69), nft(70), dot(100)]),
+		(Wild(AllCounted(2)), vec![usdt(50), nft(69)], vec![nft(70), dot(100)]),
+		(Wild(AllCounted(10)), vec![usdt(50), nft(69), nft(70), dot(100)], vec![]),
+		(
+			Wild(AllOf { id: usdt_id.clone(), fun: WildFungible }),
+			vec![usdt(50)],
+			vec![nft(69), nft(70), dot(100)],
+		),
+		(
+			Wild(AllOf { id: usdt_id.clone(), fun: WildNonFungible }),
+			vec![],
+			vec![usdt(50), nft(69), nft(70), dot(100)],
+		),
+		(
+			Wild(AllOf { id: nft_id.clone(), fun: WildNonFungible }),
+			vec![nft(69), nft(70)],
+			vec![usdt(50), dot(100)],
+		),
+		(
+			Wild(AllOfCounted { id: nft_id.clone(), fun: WildNonFungible, count: 1 }),
+			vec![nft(69)],
+			vec![usdt(50), nft(70), dot(100)],
+		),
+		(
+			Wild(AllOfCounted { id: nft_id, fun: WildNonFungible, count: 5 }),
+			vec![nft(69), nft(70)],
+			vec![usdt(50), dot(100)],
+		),
+		(
+			Wild(AllOfCounted { id: usdt_id, fun: WildFungible, count: 0 }),
+			vec![],
+			vec![usdt(50), nft(69), nft(70), dot(100)],
+		),
+		// Definite filters.
+		(assets(&[]).into(), vec![], vec![usdt(50), nft(69), nft(70), dot(100)]),
+		(dot(10).into(), vec![dot(10)], vec![usdt(50), nft(69), nft(70), dot(90)]),
+		(dot(100).into(), vec![dot(100)], vec![usdt(50), nft(69), nft(70)]),
+		// Asking for too much selects what's there.
+		(DotFilter::get(), vec![dot(100)], vec![usdt(50), nft(69), nft(70)]),
+		(UsdtFilter::get(), vec![usdt(50)], vec![nft(69), nft(70), dot(100)]),
+		(
+			assets(&[usdt(20), dot(30), nft(70)]).into(),
+			vec![usdt(20), nft(70), dot(30)],
+			vec![usdt(30), nft(69), dot(70)],
+		),
+		// Assets which aren't there are ignored.
+		(nft(71).into(), vec![], vec![usdt(50), nft(69), nft(70), dot(100)]),
+		(Asset::from((Here, 5u128)).into(), vec![], vec![usdt(50), nft(69), nft(70), dot(100)]),
+	];
+
+	for (filter, selected, remaining) in table {
+		let expected = Selection { selected: selected.into(), remaining: remaining.into() };
+		assert_eq!(apply(&filter, &holding(), MAX_ASSETS_INTO_HOLDING), Ok(expected), "{filter:?}");
+	}
+}
+
+#[test]
+fn nothing_is_lost() {
+	let filters: Vec<AssetFilter> = vec![
+		AllAssetsFilter::get(),
+		Wild(AllCounted(3)),
+		assets(&[usdt(20), dot(30), nft(70)]).into(),
+	];
+	for filter in filters {
+		let Selection { selected, remaining } =
+			apply(&filter, &holding(), MAX_ASSETS_INTO_HOLDING).unwrap();
+		let mut all = selected;
+		remaining.into_inner().into_iter().for_each(|asset| all.push(asset));
+		assert_eq!(all, holding(), "{filter:?}");
+	}
+}
+
+#[test]
+fn holding_has_a_limit() {
+	assert_eq!(
+		apply(&AllAssetsFilter::get(), &holding(), 3),
+		Err(FilterError::HoldingWouldOverflow { assets: 4, limit: 3 })
+	);
+	assert!(apply(&AllAssetsFilter::get(), &holding(), 4).is_ok());
+	assert!(apply(&AllAssetsFilter::get(), &EmptyAssets::get(), 0).is_ok());
+}
@@ -0,0 +1,159 @@
+use xcm::latest::prelude::*;
+
+use crate::{
+	constants::{ALICE, INITIAL_BALANCE},
+	instruction::*,
+	interpreter::*,
+};
+
+fn sender() -> Location {
+	Location::new(0, [AccountId32 { network: None, id: [2; 32] }])
+}
+
+fn alice() -> Location {
+	Location::new(0, [AccountId32 { network: None, id: ALICE.into() }])
+}
+
+fn dot() -> AssetId {
+	AssetId(Parent.into())
+}
+
+/// An interpreter for programs sent by `sender`, who has `INITIAL_BALANCE` DOT.
+fn interpreter() -> Interpreter {
+	let mut ledger = Ledger::default();
+	ledger.deposit(&sender(), &(Parent, INITIAL_BALANCE).into()).unwrap();
+	Interpreter::new(ledger, sender())
+}
+
+fn holding(amount: u128) -> xcm_executor::AssetsInHolding {
+	match amount {
+		0 => Default::default(),
+		amount => Asset::from((Parent, amount)).into(),
+	}
+}
+
+#[test]
+fn withdraw_and_deposit_paying_fees_leaves_alice_with_90() {
+	let mut interpreter = interpreter();
+	let steps = interpreter.execute(&withdraw_and_deposit_paying_fees());
+
+	let registers: Vec<_> =
+		steps.iter().map(|step| (step.holding.clone(), step.fees.clone())).collect();
+	assert_eq!(
+		registers,
+		vec![(holding(100), holding(0)), (holding(90), holding(10)), (holding(0), holding(10))]
+	);
+	assert!(steps.iter().all(|step| step.error.is_none() && step.origin == Some(sender())));
+
+	let ledger = interpreter.ledger();
+	assert_eq!(ledger.balance(&alice(), &dot()), 90);
+	assert_eq!(ledger.balance(&sender(), &dot()), INITIAL_BALANCE - 100);
+}
+
+#[test]
+fn withdraw_and_deposit_moves_everything() {
+	let mut interpreter = interpreter();
+	interpreter.execute(&withdraw_and_deposit());
+
+	// Nothing stops a program that doesn't pay fees here, the linter catches it.
+	assert_eq!(interpreter.ledger().balance(&alice(), &dot()), 100);
+	assert_eq!(interpreter.holding(), &holding(0));
+}
+
+#[test]
+fn withdrawn_assets_stay_in_holding() {
+	let mut interpreter = interpreter();
+	let steps = interpreter.execute(&withdraw_asset());
+
+	assert_eq!(steps.len(), 1);
+	assert_eq!(interpreter.holding(), &holding(100));
+	assert_eq!(interpreter.ledger().balance(&sender(), &dot()), INITIAL_BALANCE - 100);
+}
+
+#[test]
+fn clearing_the_origin_stops_withdrawals() {
+	let mut interpreter = interpreter();
+	let message = Xcm::<()>(vec![ClearOrigin, WithdrawAsset((Parent, 100u128).into()), ClearError]);
+	let steps = interpreter.execute(&message);
+
+	assert_eq!(steps.len(), 2);
+	assert_eq!(steps[0].origin, None);
+	assert_eq!(steps[1].index, 1);
+	assert_eq!(steps[1].error, Some(XcmError::BadOrigin));
+	assert_eq!(interpreter.error(), Some(XcmError::BadOrigin));
+	assert_eq!(interpreter.ledger().balance(&sender(), &dot()), INITIAL_BALANCE);
+}
+
+#[test]
+fn errors_leave_the_ledger_alone() {
+	let mut ledger = Ledger::default();
+	ledger.deposit(&sender(), &(Here, 1u128).into()).unwrap();
+	ledger.deposit(&sender(), &(Parent, INITIAL_BALANCE).into()).unwrap();
+	let mut interpreter = Interpreter::new(ledger.clone(), sender());
+	// There's enough of the first asset, but not of the second one.
+	let too_much: Assets = vec![(Here, 1u128).into(), (Parent, INITIAL_BALANCE + 1).into()].into();
+	interpreter.execute(&Xcm::<()>(vec![WithdrawAsset(too_much)]));
+	assert_eq!(interpreter.error(), Some(XcmError::NotWithdrawable));
+	assert_eq!(interpreter.ledger(), &ledger);
+	assert_eq!(interpreter.holding(), &holding(0));
+
+	let mut interpreter = self::interpreter();
+	let message = Xcm::<()>(vec![
+		WithdrawAsset((Parent, 100u128).into()),
+		PayFees { asset: (Here, 10u128).into() },
+	]);
+	interpreter.execute(&message);
+	assert_eq!(interpreter.error(), Some(XcmError::NotHoldingFees));
+	assert_eq!(interpreter.holding(), &holding(100));
+	assert_eq!(interpreter.fees(), &holding(0));
+}
+
+#[test]
+fn failed_deposits_leave_the_ledger_alone() {
+	let mut ledger = Ledger::default();
+	ledger.deposit(&sender(), &(Here, 1u128).into()).unwrap();
+	ledger.deposit(&sender(), &(Parent, INITIAL_BALANCE).into()).unwrap();
+	// Alice can't take any more of the second asset.
+	ledger.deposit(&alice(), &(Parent, u128::MAX).into()).unwrap();
+	let assets: Assets = vec![(Here, 1u128).into(), (Parent, 100u128).into()].into();
+	let mut withdrawn = ledger.clone();
+	for asset in assets.inner() {
+		withdrawn.withdraw(&sender(), asset).unwrap();
+	}
+
+	let mut interpreter = Interpreter::new(ledger, sender());
+	interpreter.execute(&Xcm::<()>(vec![
+		WithdrawAsset(assets.clone()),
+		DepositAsset { assets: AllCounted(2).into(), beneficiary: alice() },
+	]));
+	assert_eq!(interpreter.error(), Some(XcmError::Overflow));
+	// The first asset could be deposited, but it wasn't either.
+	assert_eq!(interpreter.ledger(), &withdrawn);
+	assert_eq!(interpreter.holding(), &xcm_executor::AssetsInHolding::from(assets));
+}
+
+#[test]
+fn origin_and_expectations() {
+	let mut interpreter = interpreter();
+	let descendant = sender().appended_with(PalletInstance(50)).unwrap();
+	let message = Xcm::<()>(vec![
+		WithdrawAsset((Parent, 100u128).into()),
+		ExpectAsset((Parent, 100u128).into()),
+		DescendOrigin(PalletInstance(50).into()),
+		ExpectOrigin(Some(descendant.clone())),
+		ExpectOrigin(Some(sender())),
+	]);
+	let steps = interpreter.execute(&message);
+
+	assert_eq!(steps.len(), 5);
+	assert_eq!(steps[2].origin, Some(descendant));
+	assert!(steps[..4].iter().all(|step| step.error.is_none()));
+	assert_eq!(steps[4].error, Some(XcmError::ExpectationFalse));
+}
+
+#[test]
+fn unsupported_instructions_fail() {
+	let mut interpreter = interpreter();
+	interpreter.execute(&Xcm::<()>(vec![RefundSurplus]));
+	assert_eq!(interpreter.error(), Some(XcmError::Unimplemented));
+}
@@ -0,0 +1,69 @@
+use std::{fs, path::Path};
+
+use serde_json::{json, Value};
+use xcm::latest::prelude::*;
+
+use crate::{asset::*, instruction::*, json::*, location::relative_to_polkadot_relay::*};
+
+fn assert_round_trips<T: Json + PartialEq + core::fmt::Debug>(value: T) {
+	assert_eq!(T::from_json(&value.to_json()).unwrap(), value);
+}
+
+#[test]
+fn fixtures_are_up_to_date() {
+	for (file, expected) in fixtures() {
+		let path = Path::new(FIXTURES_DIR).join(file);
+		let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+		assert_eq!(
+			written, expected,
+			"{file} is out of date, run `cargo run -p fundamentals --features json --bin fixtures`"
+		);
+	}
+}
+
+#[test]
+fn lesson_values_round_trip() {
+	assert_round_trips(PolkadotPara1000Alice::get());
+	assert_round_trips(KusamaPara1000::get());
+	assert_round_trips(AliceBytes::get());
+	assert_round_trips(EmptyAssets::get());
+	assert_round_trips(Usdt::get());
+	assert_round_trips(OneHundredDot::get());
+	assert_round_trips(Nft::get());
+	assert_round_trips(AllAssetsFilter::get());
+	assert_round_trips(UsdtFilter::get());
+	assert_round_trips(clear_origin_message());
+	assert_round_trips(withdraw_and_deposit_paying_fees());
+}
+
+#[test]
+fn values_are_tagged_with_their_version() {
+	assert_eq!(
+		PolkadotPara1000::get().to_json(),
+		json!({ "V5": { "parents": 0, "interior": { "X1": [{ "Parachain": 1000 }] } } })
+	);
+	assert_eq!(AllAssetsFilter::get().to_json(), json!({ "V5": { "Wild": "All" } }));
+	assert_eq!(clear_origin_message().to_json(), json!({ "V5": ["ClearOrigin"] }));
+}
+
+#[test]
+fn older_versions_are_read() {
+	let parent = json!({ "V3": { "parents": 1, "interior": "Here" } });
+	assert_eq!(Location::from_json(&parent).unwrap(), Location::parent());
+
+	let dot = json!({
+		"V4": { "id": { "parents": 1, "interior": "Here" }, "fun": { "Fungible": 10 } },
+	});
+	assert_eq!(Asset::from_json(&dot).unwrap(), (Parent, 10u128).into());
+
+	// Only the latest version of filters is known.
+	assert!(AssetFilter::from_json(&json!({ "V4": { "Wild": "All" } })).is_err());
+}
+
+#[test]
+fn what_the_latest_version_lost_is_refused() {
+	let westend = json!({
+		"V4": { "parents": 1, "interior": { "X1": [{ "GlobalConsensus": "Westend" }] } },
+	});
+	assert!(Location::from_json(&westend).is_err());
+}
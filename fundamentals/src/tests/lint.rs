@@ -0,0 +1,154 @@
+use crate::{constants::ALICE, instruction::*, lint::*};
+use xcm::latest::prelude::*;
+
+fn alice() -> Location {
+	AccountId32 { id: ALICE.into(), network: None }.into()
+}
+
+fn transact() -> Instruction<()> {
+	Transact {
+		origin_kind: OriginKind::SovereignAccount,
+		fallback_max_weight: None,
+		call: Vec::new().into(),
+	}
+}
+
+#[test]
+fn lesson_programs() {
+	use Rule::*;
+
+	let cases: Vec<(Xcm<()>, Vec<Diagnostic>)> = vec![
+		(clear_origin_message(), vec![Diagnostic::new(MissingFees, [])]),
+		(withdraw_asset(), vec![Diagnostic::new(MissingFees, [])]),
+		(
+			withdraw_and_deposit(),
+			vec![Diagnostic::new(MissingFees, [1]), Diagnostic::new(UncountedWildcard, [1])],
+		),
+		(withdraw_and_deposit_paying_fees(), vec![Diagnostic::new(UncountedWildcard, [2])]),
+	];
+
+	for (program, expected) in cases {
+		assert_eq!(lint(&program), expected, "{program:?}");
+	}
+}
+
+#[test]
+fn counted_deposit_is_clean() {
+	let message = Xcm::<()>::builder()
+		.withdraw_asset((Parent, 100u128))
+		.pay_fees((Parent, 10u128))
+		.deposit_asset(AllCounted(1), alice())
+		.build();
+
+	assert_eq!(lint(&message), vec![]);
+}
+
+#[test]
+fn local_programs_need_no_fees() {
+	assert_eq!(lint_local(&withdraw_asset()), vec![]);
+	assert_eq!(
+		lint_local(&withdraw_and_deposit()),
+		vec![Diagnostic::new(Rule::UncountedWildcard, [1])]
+	);
+}
+
+#[test]
+fn fees_must_be_in_holding() {
+	let too_much = Xcm::<()>::builder()
+		.withdraw_asset((Parent, 100u128))
+		.pay_fees((Parent, 200u128))
+		.deposit_asset(AllCounted(1), alice())
+		.build();
+	let other_asset = Xcm::<()>::builder()
+		.withdraw_asset((Parent, 100u128))
+		.pay_fees((Here, 10u128))
+		.deposit_asset(AllCounted(1), alice())
+		.build();
+
+	assert_eq!(lint(&too_much), vec![Diagnostic::new(Rule::FeesNotInHolding, [1])]);
+	assert_eq!(lint(&other_asset), vec![Diagnostic::new(Rule::FeesNotInHolding, [1])]);
+}
+
+#[test]
+fn transact_needs_an_origin() {
+	let message = Xcm(vec![
+		WithdrawAsset((Parent, 100u128).into()),
+		PayFees { asset: (Parent, 10u128).into() },
+		transact(),
+		ClearOrigin,
+		transact(),
+		ExecuteWithOrigin { descendant_origin: None, xcm: Xcm(vec![transact()]) },
+	]);
+
+	assert_eq!(
+		lint(&message),
+		vec![
+			Diagnostic::new(Rule::TransactWithoutOrigin, [4]),
+			Diagnostic::new(Rule::TransactWithoutOrigin, [5, 0]),
+		]
+	);
+}
+
+#[test]
+fn remote_programs_are_linted() {
+	let transfer = |remote_fees: Option<Asset>, assets: AssetFilter, remote_xcm: Xcm<()>| {
+		Xcm::<()>(vec![
+			WithdrawAsset((Parent, 100u128).into()),
+			PayFees { asset: (Parent, 10u128).into() },
+			InitiateTransfer {
+				destination: (Parent, Parachain(1000)).into(),
+				remote_fees: remote_fees
+					.map(|fees| AssetTransferFilter::ReserveWithdraw(Definite(fees.into()))),
+				preserve_origin: false,
+				assets: vec![AssetTransferFilter::ReserveWithdraw(assets)].try_into().unwrap(),
+				remote_xcm,
+			},
+		])
+	};
+
+	// The origin is cleared on the destination and `All` is used there.
+	let message = transfer(
+		Some((Parent, 10u128).into()),
+		AllCounted(1).into(),
+		Xcm(vec![DepositAsset { assets: All.into(), beneficiary: alice() }, transact()]),
+	);
+	assert_eq!(
+		lint(&message),
+		vec![
+			Diagnostic::new(Rule::UncountedWildcard, [2, 0]),
+			Diagnostic::new(Rule::TransactWithoutOrigin, [2, 1]),
+		]
+	);
+
+	// Nobody pays on the destination.
+	let message = transfer(
+		None,
+		AllCounted(1).into(),
+		Xcm(vec![DepositAsset { assets: AllCounted(1).into(), beneficiary: alice() }]),
+	);
+	assert_eq!(lint(&message), vec![Diagnostic::new(Rule::MissingFees, [2, 0])]);
+
+	// The destination pays with an asset that was not transferred.
+	let message = transfer(
+		None,
+		Definite((Parent, 50u128).into()),
+		Xcm(vec![
+			PayFees { asset: (Here, 10u128).into() },
+			DepositAsset { assets: AllCounted(1).into(), beneficiary: alice() },
+		]),
+	);
+	assert_eq!(lint(&message), vec![Diagnostic::new(Rule::FeesNotInHolding, [2, 0])]);
+}
+
+#[test]
+fn diagnostics_show_their_path_and_rule() {
+	for rule in Rule::ALL {
+		assert!(!rule.description().is_empty());
+	}
+
+	let diagnostic = Diagnostic::new(Rule::MissingFees, [2, 0]);
+	assert_eq!(
+		diagnostic.to_string(),
+		format!("[2.0] MissingFees: {}", Rule::MissingFees.description())
+	);
+}
@@ -2,10 +2,29 @@
 #[cfg(feature = "location")]
 pub mod location;
 
+#[cfg(feature = "location")]
+pub mod calculator;
+
 /// Lesson 2
 #[cfg(feature = "asset")]
 pub mod asset;
 
+#[cfg(feature = "asset")]
+pub mod filter;
+
 /// Lesson 4
 #[cfg(feature = "instruction")]
 pub mod instruction;
+
+/// Lesson 5
+#[cfg(feature = "versions")]
+pub mod versions;
+
+#[cfg(feature = "lint")]
+pub mod lint;
+
+#[cfg(feature = "interpreter")]
+pub mod interpreter;
+
+#[cfg(feature = "json")]
+pub mod json;
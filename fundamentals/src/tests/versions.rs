@@ -0,0 +1,101 @@
+use xcm::{latest::prelude::*, v4, IdentifyVersion, VersionedLocation, VersionedXcm};
+
+use crate::{constants, instruction::*, versions::*};
+
+#[test]
+fn withdraw_and_deposit_for_v4_correct() {
+	let message = withdraw_and_deposit_for_v4();
+
+	assert_eq!(
+		message,
+		Xcm(vec![
+			WithdrawAsset((Parent, 100u128).into()),
+			BuyExecution { fees: (Parent, 10u128).into(), weight_limit: Unlimited },
+			DepositAsset {
+				assets: All.into(),
+				beneficiary: AccountId32 { id: constants::ALICE.into(), network: None }.into()
+			}
+		])
+	);
+}
+
+#[test]
+fn older_chains_understand_buy_execution() {
+	for version in [3, 4, 5] {
+		let converted = convert_xcm(withdraw_and_deposit_for_v4().into(), version).unwrap();
+		assert_eq!(converted.identify_version(), version);
+		assert_eq!(
+			convert_xcm(converted, 5),
+			Ok(VersionedXcm::from(withdraw_and_deposit_for_v4()))
+		);
+	}
+}
+
+#[test]
+fn pay_fees_is_new_in_v5() {
+	let message = VersionedXcm::from(withdraw_and_deposit_paying_fees());
+
+	assert!(convert_xcm(message.clone(), 5).is_ok());
+	for version in [3, 4] {
+		assert_eq!(
+			convert_xcm(message.clone(), version),
+			Err(ConversionError::Instruction { index: 1, name: "PayFees".into() })
+		);
+	}
+}
+
+#[test]
+fn initiate_transfer_is_new_in_v5() {
+	let message = Xcm::<()>(vec![
+		ClearOrigin,
+		InitiateTransfer {
+			destination: (Parent, Parachain(1000)).into(),
+			remote_fees: None,
+			preserve_origin: false,
+			assets: vec![AssetTransferFilter::Teleport(AllCounted(1).into())].try_into().unwrap(),
+			remote_xcm: Xcm::new(),
+		},
+	]);
+
+	assert_eq!(
+		convert_xcm(message.into(), 4),
+		Err(ConversionError::Instruction { index: 1, name: "InitiateTransfer".into() })
+	);
+}
+
+#[test]
+fn westend_is_gone_in_v5() {
+	let location = VersionedLocation::V4(v4::Location::new(
+		1,
+		[v4::Junction::Parachain(1000), v4::Junction::GlobalConsensus(v4::NetworkId::Westend)],
+	));
+
+	assert!(convert_location(location.clone(), 3).is_ok());
+	assert_eq!(
+		convert_location(location, 5),
+		Err(ConversionError::Junction { index: 1, junction: "GlobalConsensus(Westend)".into() })
+	);
+}
+
+#[test]
+fn locations_go_back_and_forth() {
+	let location = Location::new(1, [Parachain(1000), PalletInstance(50), GeneralIndex(1984)]);
+
+	for version in [3, 4, 5] {
+		let converted = convert_location(location.clone().into(), version).unwrap();
+		assert_eq!(converted.identify_version(), version);
+		assert_eq!(convert_location(converted, 5), Ok(location.clone().into()));
+	}
+}
+
+#[test]
+fn unknown_versions() {
+	let message = VersionedXcm::from(clear_origin_message());
+
+	assert_eq!(convert_xcm(message.clone(), 2), Err(ConversionError::UnknownVersion(2)));
+	assert_eq!(convert_xcm(message, 6), Err(ConversionError::UnknownVersion(6)));
+	assert_eq!(
+		convert_location(Location::here().into(), 6),
+		Err(ConversionError::UnknownVersion(6))
+	);
+}
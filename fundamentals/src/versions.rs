@@ -0,0 +1,154 @@
+//! # Fundamentals Lesson 5
+//!
+//! Not every chain speaks the latest version of XCM.
+//! Before sending a program, `pallet-xcm` converts it to the version the destination knows,
+//! and the conversion fails when the program uses something the older version doesn't have.
+//! `PayFees` and `InitiateTransfer`, for example, are new in v5.
+//! Locations are converted too, and can fail the other way: v5 dropped the `Westend`,
+//! `Rococo` and `Wococo` networks.
+//!
+//! The helpers at the end of this module convert programs and locations, and tell us which
+//! instruction or junction blocked the conversion.
+
+use core::fmt;
+
+use codec::Decode;
+use frame_support::dispatch::GetDispatchInfo;
+use xcm::{latest::prelude::*, v3, v4, IntoVersion, Version, VersionedLocation, VersionedXcm};
+
+use crate::constants::ALICE;
+
+/// The last lesson paid fees with `PayFees`, which v4 chains don't understand.
+/// Write `withdraw_and_deposit_paying_fees` again so it can be sent to them.
+/// Use `BuyExecution` with the same 10 planks and no weight limit.
+pub fn withdraw_and_deposit_for_v4() -> Xcm<()> {
+	let alice_bytes: [u8; 32] = ALICE.into();
+	let message = Xcm::builder()
+		.withdraw_asset((Parent, 100u128))
+		.buy_execution((Parent, 10u128), Unlimited)
+		.deposit_asset(All, alice_bytes)
+		.build();
+
+	message
+}
+
+/// The oldest version the helpers convert to and from.
+pub const OLDEST_VERSION: Version = 3;
+
+/// Why a conversion failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConversionError {
+	/// Only versions from [`OLDEST_VERSION`] to the latest one exist.
+	UnknownVersion(Version),
+	/// The instruction at `index` has no equivalent in the target version.
+	/// Nested programs are reported by the instruction holding them.
+	Instruction { index: usize, name: String },
+	/// The junction at `index` of the interior has no equivalent in the target version.
+	Junction { index: usize, junction: String },
+	/// The conversion failed, but every instruction or junction converts on its own.
+	Unknown,
+}
+
+/// Converts `xcm` to `version`.
+pub fn convert_xcm<Call>(
+	xcm: VersionedXcm<Call>,
+	version: Version,
+) -> Result<VersionedXcm<Call>, ConversionError>
+where
+	Call: Decode + GetDispatchInfo + fmt::Debug,
+{
+	ensure_known(version)?;
+	if let Ok(converted) = xcm.clone().into_version(version) {
+		return Ok(converted);
+	}
+	// Convert each instruction on its own to find the one to blame.
+	let blocker = instructions(xcm).into_iter().enumerate().find_map(|(index, (name, xcm))| {
+		xcm.into_version(version).is_err().then_some(ConversionError::Instruction { index, name })
+	});
+	Err(blocker.unwrap_or(ConversionError::Unknown))
+}
+
+/// Converts `location` to `version`.
+pub fn convert_location(
+	location: VersionedLocation,
+	version: Version,
+) -> Result<VersionedLocation, ConversionError> {
+	ensure_known(version)?;
+	if let Ok(converted) = location.clone().into_version(version) {
+		return Ok(converted);
+	}
+	// Convert each junction on its own to find the one to blame.
+	let blocker = junctions(&location).into_iter().enumerate().find_map(
+		|(index, (junction, location))| {
+			location
+				.into_version(version)
+				.is_err()
+				.then_some(ConversionError::Junction { index, junction })
+		},
+	);
+	Err(blocker.unwrap_or(ConversionError::Unknown))
+}
+
+fn ensure_known(version: Version) -> Result<(), ConversionError> {
+	match (OLDEST_VERSION..=xcm::latest::VERSION).contains(&version) {
+		true => Ok(()),
+		false => Err(ConversionError::UnknownVersion(version)),
+	}
+}
+
+/// Every instruction of `xcm` as a program of its own, in the same version, with its name.
+fn instructions<Call: fmt::Debug>(xcm: VersionedXcm<Call>) -> Vec<(String, VersionedXcm<Call>)> {
+	match xcm {
+		VersionedXcm::V3(xcm) => xcm
+			.0
+			.into_iter()
+			.map(|instruction| (name(&instruction), VersionedXcm::V3(v3::Xcm(vec![instruction]))))
+			.collect(),
+		VersionedXcm::V4(xcm) => xcm
+			.0
+			.into_iter()
+			.map(|instruction| (name(&instruction), VersionedXcm::V4(v4::Xcm(vec![instruction]))))
+			.collect(),
+		VersionedXcm::V5(xcm) => xcm
+			.0
+			.into_iter()
+			.map(|instruction| (name(&instruction), VersionedXcm::V5(Xcm(vec![instruction]))))
+			.collect(),
+	}
+}
+
+/// Every junction of the interior of `location` as a location of its own, in the same version.
+fn junctions(location: &VersionedLocation) -> Vec<(String, VersionedLocation)> {
+	match location {
+		VersionedLocation::V3(location) => location
+			.interior
+			.iter()
+			.map(|junction| {
+				let alone = v3::MultiLocation::new(0, v3::Junctions::X1(*junction));
+				(format!("{junction:?}"), VersionedLocation::V3(alone))
+			})
+			.collect(),
+		VersionedLocation::V4(location) => location
+			.interior()
+			.iter()
+			.map(|junction| {
+				let alone = v4::Location::new(0, [*junction]);
+				(format!("{junction:?}"), VersionedLocation::V4(alone))
+			})
+			.collect(),
+		VersionedLocation::V5(location) => location
+			.interior()
+			.iter()
+			.map(|junction| {
+				let alone = Location::new(0, [*junction]);
+				(format!("{junction:?}"), VersionedLocation::V5(alone))
+			})
+			.collect(),
+	}
+}
+
+/// The name of an instruction, without its operands.
+fn name(instruction: &impl fmt::Debug) -> String {
+	let debug = format!("{instruction:?}");
+	debug.split(|c: char| !c.is_alphanumeric()).next().unwrap_or_default().to_string()
+}
@@ -1,23 +1,127 @@
-use frame_support::{genesis_builder_helper::build_state, weights::Weight};
+use codec::{Decode, Encode};
+use frame_support::{
+	genesis_builder_helper::{build_state, get_preset},
+	traits::{Get, PalletInfoAccess},
+	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight, WeightToFee as _},
+};
 use pallet_aura::Authorities;
+use pallet_xcm::ExecutionError;
+use scale_info::TypeInfo;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	traits::Block as BlockT,
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult,
+	ApplyExtrinsicResult, DispatchError, ModuleError,
 };
-use sp_std::prelude::Vec;
+use sp_std::{prelude::Vec, vec};
 use sp_version::RuntimeVersion;
+use xcm::{
+	latest::prelude::*, VersionedAssetId, VersionedAssets, VersionedLocation, VersionedXcm,
+};
+use xcm_executor::{traits::ExecuteXcm, XcmExecutor};
+use xcm_runtime_apis::fees::Error as XcmPaymentApiError;
 
 // Local module imports
 use super::{
+	configs::xcm_config::{
+		CustomParaLocation, FeePerSecond, HereLocation, RelayLocation, SiblingParaLocation,
+		XcmConfig, XcmRouter,
+	},
 	AccountId, Balance, Block, ConsensusHook, Executive, InherentDataExt, Nonce, ParachainSystem,
-	Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment,
-	SLOT_DURATION, VERSION,
+	PolkadotXcm, Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System,
+	TransactionPayment, WeightToFee, SLOT_DURATION, VERSION,
 };
 
+/// What happened to a single instruction of a recorded XCM program.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub enum InstructionOutcome {
+	/// The instruction was executed successfully.
+	Executed,
+	/// Execution stopped at this instruction because of this error.
+	Failed(ExecutionError),
+	/// Execution never reached this instruction.
+	NotExecuted,
+}
+
+/// An XCM program executed on this chain, as recorded by the `XcmRecorder`.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub struct RecordedXcm {
+	/// The exact program that was executed locally.
+	pub program: VersionedXcm<()>,
+	/// The outcome of each instruction in `program`, in the same order.
+	pub instructions: Vec<InstructionOutcome>,
+	/// The messages sent to other chains while executing `program`.
+	pub forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
+}
+
+/// Errors returned by the [`XcmRecorderApi`].
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub enum XcmRecorderApiError {
+	/// The extrinsic couldn't be applied, for example because of a wrong nonce.
+	InvalidExtrinsic,
+	/// The XCM couldn't be converted to the latest version.
+	VersionedConversionFailed,
+	/// The message was handed to the executor, but the `XcmRecorder` didn't capture it.
+	NotRecorded,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Exposes the XCM programs executed on this chain, so failed transfers can be explained
+	/// without replaying blocks by hand.
+	pub trait XcmRecorderApi<Call: codec::Codec> {
+		/// Applies `extrinsic` and returns the XCM it executed locally, if there was any.
+		fn recorded_xcm_for_extrinsic(
+			extrinsic: <Block as BlockT>::Extrinsic,
+		) -> Result<Option<RecordedXcm>, XcmRecorderApiError>;
+
+		/// Executes `xcm` as if it came in from `origin` and returns what the `XcmRecorder`
+		/// captured.
+		fn recorded_xcm_for_message(
+			origin: VersionedLocation,
+			xcm: VersionedXcm<Call>,
+		) -> Result<RecordedXcm, XcmRecorderApiError>;
+	}
+}
+
+/// The assets our `Trader` takes for fees.
+fn acceptable_payment_assets() -> Vec<AssetId> {
+	vec![
+		AssetId(HereLocation::get()),
+		AssetId(RelayLocation::get()),
+		AssetId(CustomParaLocation::get()),
+		AssetId(SiblingParaLocation::get()),
+	]
+}
+
+/// Gets the failing instruction out of a failed `PolkadotXcm` dispatch, if that's what failed.
+fn failed_instruction(error: &DispatchError) -> Option<(u8, ExecutionError)> {
+	let DispatchError::Module(ModuleError { index, error, .. }) = error else { return None };
+	if *index as usize != <PolkadotXcm as PalletInfoAccess>::index() {
+		return None;
+	}
+	match pallet_xcm::Error::<Runtime>::decode(&mut &error[..]).ok()? {
+		pallet_xcm::Error::LocalExecutionIncompleteWithError { index, error } => Some((index, error)),
+		_ => None,
+	}
+}
+
+/// Every instruction before the failing one was executed, and none after it.
+fn instruction_outcomes(
+	length: usize,
+	failure: Option<(u8, ExecutionError)>,
+) -> Vec<InstructionOutcome> {
+	(0..length)
+		.map(|i| match &failure {
+			Some((index, _)) if i < *index as usize => InstructionOutcome::Executed,
+			Some((index, error)) if i == *index as usize => InstructionOutcome::Failed(error.clone()),
+			Some(_) => InstructionOutcome::NotExecuted,
+			None => InstructionOutcome::Executed,
+		})
+		.collect()
+}
+
 impl_runtime_apis! {
 	impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
 		fn slot_duration() -> sp_consensus_aura::SlotDuration {
@@ -165,6 +269,109 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl XcmRecorderApi<Block, RuntimeCall> for Runtime {
+		fn recorded_xcm_for_extrinsic(
+			extrinsic: <Block as BlockT>::Extrinsic,
+		) -> Result<Option<RecordedXcm>, XcmRecorderApiError> {
+			use xcm_builder::InspectMessageQueues;
+			use xcm_executor::traits::RecordXcm;
+
+			// The recorded program is reverted together with a failed extrinsic,
+			// so we hold on to the one passed to `execute`.
+			let executed_program = match &extrinsic.function {
+				RuntimeCall::PolkadotXcm(pallet_xcm::Call::execute { message, .. }) =>
+					Xcm::<RuntimeCall>::try_from(*message.clone()).ok(),
+				_ => None,
+			};
+			PolkadotXcm::set_record_xcm(true);
+			XcmRouter::clear_messages();
+			let result = Executive::apply_extrinsic(extrinsic)
+				.map_err(|_| XcmRecorderApiError::InvalidExtrinsic)?;
+			let Some(program) = PolkadotXcm::recorded_xcm()
+				.or_else(|| executed_program.map(|xcm| Xcm::<()>::from(xcm)))
+			else {
+				return Ok(None);
+			};
+			let failure = result.err().as_ref().and_then(failed_instruction);
+			Ok(Some(RecordedXcm {
+				instructions: instruction_outcomes(program.len(), failure),
+				program: VersionedXcm::from(program),
+				forwarded_xcms: XcmRouter::get_messages(),
+			}))
+		}
+
+		fn recorded_xcm_for_message(
+			origin: VersionedLocation,
+			xcm: VersionedXcm<RuntimeCall>,
+		) -> Result<RecordedXcm, XcmRecorderApiError> {
+			use xcm_builder::InspectMessageQueues;
+			use xcm_executor::traits::RecordXcm;
+
+			let origin = Location::try_from(origin)
+				.map_err(|_| XcmRecorderApiError::VersionedConversionFailed)?;
+			let xcm = Xcm::<RuntimeCall>::try_from(xcm)
+				.map_err(|_| XcmRecorderApiError::VersionedConversionFailed)?;
+			PolkadotXcm::set_record_xcm(true);
+			XcmRouter::clear_messages();
+			let mut hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+			let outcome = XcmExecutor::<XcmConfig>::prepare_and_execute(
+				origin,
+				xcm,
+				&mut hash,
+				Weight::MAX,
+				Weight::zero(),
+			);
+			let program = PolkadotXcm::recorded_xcm().ok_or(XcmRecorderApiError::NotRecorded)?;
+			let failure = match outcome {
+				Outcome::Complete { .. } => None,
+				Outcome::Incomplete { error, .. } | Outcome::Error(error) =>
+					Some((error.index, error.error.into())),
+			};
+			Ok(RecordedXcm {
+				instructions: instruction_outcomes(program.len(), failure),
+				program: VersionedXcm::from(program),
+				forwarded_xcms: XcmRouter::get_messages(),
+			})
+		}
+	}
+
+	impl xcm_runtime_apis::fees::XcmPaymentApi<Block> for Runtime {
+		fn query_acceptable_payment_assets(
+			xcm_version: xcm::Version,
+		) -> Result<Vec<VersionedAssetId>, XcmPaymentApiError> {
+			PolkadotXcm::query_acceptable_payment_assets(xcm_version, acceptable_payment_assets())
+		}
+
+		fn query_weight_to_asset_fee(
+			weight: Weight,
+			asset: VersionedAssetId,
+		) -> Result<u128, XcmPaymentApiError> {
+			let asset = AssetId::try_from(asset)
+				.map_err(|_| XcmPaymentApiError::VersionedConversionFailed)?;
+			// Our token and WND are charged by `UsingComponents`, the tokens of both instances
+			// by `FixedRateOfFungible`, at the same price per second.
+			if asset == AssetId(HereLocation::get()) || asset == AssetId(RelayLocation::get()) {
+				Ok(WeightToFee::weight_to_fee(&weight))
+			} else if acceptable_payment_assets().contains(&asset) {
+				Ok(FeePerSecond::get() * weight.ref_time() as u128 /
+					WEIGHT_REF_TIME_PER_SECOND as u128)
+			} else {
+				Err(XcmPaymentApiError::AssetNotFound)
+			}
+		}
+
+		fn query_xcm_weight(message: VersionedXcm<()>) -> Result<Weight, XcmPaymentApiError> {
+			PolkadotXcm::query_xcm_weight(message)
+		}
+
+		fn query_delivery_fees(
+			destination: VersionedLocation,
+			message: VersionedXcm<()>,
+		) -> Result<VersionedAssets, XcmPaymentApiError> {
+			PolkadotXcm::query_delivery_fees(destination, message)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
@@ -249,12 +456,12 @@ impl_runtime_apis! {
 			build_state::<RuntimeGenesisConfig>(config)
 		}
 
-		fn get_preset(_id: &Option<sp_genesis_builder::PresetId>) -> Option<Vec<u8>> {
-			None
+		fn get_preset(id: &Option<sp_genesis_builder::PresetId>) -> Option<Vec<u8>> {
+			get_preset::<RuntimeGenesisConfig>(id, crate::genesis_config_presets::get_preset)
 		}
 
 		fn preset_names() -> Vec<sp_genesis_builder::PresetId> {
-			Vec::new()
+			crate::genesis_config_presets::preset_names()
 		}
 	}
 }
@@ -40,6 +40,9 @@ use super::{
 };
 use xcm_config::{RelayLocation, XcmOriginToTransactDispatchOrigin};
 
+#[cfg(not(feature = "runtime-benchmarks"))]
+use crate::pallets::xcm_failures::EmitFailures;
+
 parameter_types! {
 	pub const Version: RuntimeVersion = VERSION;
 
@@ -220,9 +223,10 @@ impl pallet_message_queue::Config for Runtime {
 		cumulus_primitives_core::AggregateMessageOrigin,
 	>;
 	#[cfg(not(feature = "runtime-benchmarks"))]
+	// We wrap the executor so failed messages report which instruction failed and why.
 	type MessageProcessor = xcm_builder::ProcessXcmMessage<
 		AggregateMessageOrigin,
-		xcm_executor::XcmExecutor<xcm_config::XcmConfig>,
+		EmitFailures<Runtime, xcm_executor::XcmExecutor<xcm_config::XcmConfig>>,
 		RuntimeCall,
 	>;
 	type Size = u32;
@@ -310,3 +314,7 @@ impl pallet_collator_selection::Config for Runtime {
 	type ValidatorRegistration = Session;
 	type WeightInfo = (); // Configure based on benchmarking results.
 }
+
+impl crate::pallets::xcm_failures::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+}
@@ -1,9 +1,16 @@
 use super::RelayLocation;
 
 use frame_support::traits::{EverythingBut, Equals};
-use xcm_builder::{AllowTopLevelPaidExecutionFrom, TakeWeightCredit};
+use xcm_builder::{
+    AllowHrmpNotificationsFromRelayChain, AllowTopLevelPaidExecutionFrom, TakeWeightCredit,
+    TrailingSetTopicAsId,
+};
 
-pub type Barrier = (
+// Messages ending in `SetTopic` get identified by that topic, so they can be
+// followed across chains.
+pub type Barrier = TrailingSetTopicAsId<(
     TakeWeightCredit,
     AllowTopLevelPaidExecutionFrom<EverythingBut<Equals<RelayLocation>>>,
-);
+    // The relay chain notifies us about HRMP channels without paying for execution.
+    AllowHrmpNotificationsFromRelayChain,
+)>;
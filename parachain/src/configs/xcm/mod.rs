@@ -1,17 +1,20 @@
 // We split the XCM config between multiple files for convenience.
 mod asset_transactor;
 mod barrier;
+mod call_filter;
 mod reserves_and_teleports;
 
 use crate::{
-	AccountId, AllPalletsWithSystem, Balances, ParachainInfo,
+	AccountId, AllPalletsWithSystem, Balances, HrmpChannels, ParachainInfo,
 	ParachainSystem, PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, WeightToFee,
 	XcmpQueue,
 };
+use cumulus_primitives_core::ParaId;
+use cumulus_primitives_utility::XcmFeesTo32ByteAccount;
 use frame_support::{
 	parameter_types,
 	traits::{ConstU32, Contains, Everything, Nothing},
-	weights::Weight,
+	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight, WeightToFee as _},
 };
 use frame_system::EnsureRoot;
 use pallet_xcm::XcmPassthrough;
@@ -20,8 +23,9 @@ use polkadot_runtime_common::impls::ToAuthor;
 use sp_runtime::traits::AccountIdConversion;
 use xcm::latest::prelude::*;
 use xcm_builder::{
-	AccountId32Aliases, DescribeAllTerminal, DescribeFamily,
-	EnsureXcmOrigin, FixedWeightBounds, FrameTransactionalProcessor, HashedDescription,
+	AccountId32Aliases, DescribeAccountKey20Terminal, DescribeAllTerminal, DescribeFamily,
+	EnsureXcmOrigin, FixedRateOfFungible, FixedWeightBounds, FrameTransactionalProcessor,
+	HashedDescription,
 	RelayChainAsNative, SiblingParachainAsNative, SignedAccountId32AsNative,
 	SignedToAccountId32, SovereignSignedViaLocation,
 	UsingComponents, WithUniqueTopic,
@@ -29,6 +33,11 @@ use xcm_builder::{
 use xcm_executor::XcmExecutor;
 
 pub const ASSET_HUB_ID: u32 = 1000;
+/// The workshop network runs this runtime twice, as two sibling parachains.
+pub const CUSTOM_PARA_ID: u32 = 2000;
+pub const SIBLING_PARA_ID: u32 = 2001;
+/// One WND, the token of the Westend relay chain.
+pub const WND_UNITS: u128 = 1_000_000_000_000;
 
 parameter_types! {
 	pub const RelayLocation: Location = Location::parent();
@@ -41,6 +50,8 @@ parameter_types! {
 	// and prepend `UniversalLocation` with `GlobalConsensus(RelayNetwork::get())`.
 	pub UniversalLocation: InteriorLocation = Parachain(ParachainInfo::parachain_id().into()).into();
 	pub TreasuryAccount: AccountId = TREASURY_PALLET_ID.into_account_truncating();
+	pub CustomParaLocation: Location = Location::new(1, [Parachain(CUSTOM_PARA_ID)]);
+	pub SiblingParaLocation: Location = Location::new(1, [Parachain(SIBLING_PARA_ID)]);
 }
 
 /// Type for specifying how a `Location` can be converted into an `AccountId`. This is used
@@ -51,6 +62,9 @@ pub type LocationToAccountId = (
 	HashedDescription<AccountId, DescribeFamily<DescribeAllTerminal>>,
 	// Straight up local `AccountId32` origins just alias directly to `AccountId`.
 	AccountId32Aliases<RelayNetwork, AccountId>,
+	// Local `AccountKey20` locations, like the beneficiary of a transfer from a chain with
+	// Ethereum style accounts, get an account from the hash of their key.
+	HashedDescription<AccountId, DescribeAccountKey20Terminal>,
 );
 
 /// This is the type we use to convert an (incoming) XCM origin into a local `Origin` instance,
@@ -79,8 +93,23 @@ parameter_types! {
 	pub UnitWeightCost: Weight = Weight::from_parts(1_000_000_000, 64 * 1024);
 	pub const MaxInstructions: u32 = 100;
 	pub const MaxAssetsIntoHolding: u32 = 64;
+	// The tokens of both instances of this runtime cost the same per second of execution
+	// as `UsingComponents` charges in our own token.
+	pub FeePerSecond: u128 =
+		WeightToFee::weight_to_fee(&Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND, 0));
+	pub CustomParaFeePerSecond: (AssetId, u128, u128) =
+		(CustomParaLocation::get().into(), FeePerSecond::get(), 0);
+	pub SiblingParaFeePerSecond: (AssetId, u128, u128) =
+		(SiblingParaLocation::get().into(), FeePerSecond::get(), 0);
+	// Fees paid in a sibling's token stay in that token, in the treasury.
+	pub XcmFeesReceiver: Option<AccountId> = Some(TreasuryAccount::get());
 }
 
+/// Takes the fees paid in a foreign token and deposits them, still in that token, into
+/// [`XcmFeesReceiver`].
+pub type ForeignFeesToTreasury =
+	XcmFeesTo32ByteAccount<asset_transactor::AssetTransactor, AccountId, XcmFeesReceiver>;
+
 pub struct ParentOrParentsExecutivePlurality;
 impl Contains<Location> for ParentOrParentsExecutivePlurality {
 	fn contains(location: &Location) -> bool {
@@ -103,6 +132,10 @@ impl xcm_executor::Config for XcmConfig {
 	type Trader = (
 		UsingComponents<WeightToFee, HereLocation, AccountId, Balances, ToAuthor<Runtime>>,
 		UsingComponents<WeightToFee, RelayLocation, AccountId, Balances, ToAuthor<Runtime>>,
+		// Both instances of this runtime take each other's native token for fees.
+		// They're foreign assets here, so `UsingComponents` would mint our token for the author.
+		FixedRateOfFungible<CustomParaFeePerSecond, ForeignFeesToTreasury>,
+		FixedRateOfFungible<SiblingParaFeePerSecond, ForeignFeesToTreasury>,
 	);
 	type ResponseHandler = PolkadotXcm;
 	type AssetTrap = PolkadotXcm;
@@ -116,12 +149,13 @@ impl xcm_executor::Config for XcmConfig {
 	type MessageExporter = ();
 	type UniversalAliases = Nothing;
 	type CallDispatcher = RuntimeCall;
-	type SafeCallFilter = Everything;
+	// Only calls that are safe to dispatch cross-chain can be used with `Transact`.
+	type SafeCallFilter = call_filter::SafeCallFilter;
 	type Aliasers = Nothing;
 	type TransactionalProcessor = FrameTransactionalProcessor;
-	type HrmpNewChannelOpenRequestHandler = ();
-	type HrmpChannelAcceptedHandler = ();
-	type HrmpChannelClosingHandler = ();
+	type HrmpNewChannelOpenRequestHandler = HrmpChannels;
+	type HrmpChannelAcceptedHandler = HrmpChannels;
+	type HrmpChannelClosingHandler = HrmpChannels;
 	type XcmRecorder = PolkadotXcm;
 	type XcmEventEmitter = PolkadotXcm;
 }
@@ -143,9 +177,8 @@ impl pallet_xcm::Config for Runtime {
 	type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
 	type XcmRouter = XcmRouter;
 	type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
-	type XcmExecuteFilter = Everything;
-	// ^ Disable dispatchable execute on the XCM pallet.
-	// Needs to be `Everything` for local testing.
+	// Local accounts can only execute programs that handle their own assets.
+	type XcmExecuteFilter = xcm_filters::XcmExecuteFilter<RuntimeCall>;
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 	type XcmTeleportFilter = Everything;
 	type XcmReserveTransferFilter = Nothing;
@@ -172,3 +205,27 @@ impl cumulus_pallet_xcm::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 }
+
+/// Siblings we open HRMP channels with, without going through governance.
+pub struct TrustedHrmpSiblings;
+impl Contains<ParaId> for TrustedHrmpSiblings {
+	fn contains(para_id: &ParaId) -> bool {
+		matches!(u32::from(*para_id), ASSET_HUB_ID | SIBLING_PARA_ID)
+	}
+}
+
+parameter_types! {
+	// The index of the `Hrmp` pallet in the `construct_runtime!` of `westend_runtime`.
+	pub const RelayHrmpPalletIndex: u8 = 51;
+	// Paid with WND from our sovereign account on the relay chain, so `Here` is WND.
+	pub RelayHrmpFees: Asset = (Here, WND_UNITS).into();
+}
+
+impl crate::pallets::hrmp_channels::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type TrustedSiblings = TrustedHrmpSiblings;
+	type SelfParaId = ParachainInfo;
+	type XcmSender = XcmRouter;
+	type RelayHrmpPalletIndex = RelayHrmpPalletIndex;
+	type RelayFees = RelayHrmpFees;
+}
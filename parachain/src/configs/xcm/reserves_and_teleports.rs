@@ -1,10 +1,12 @@
-use super::ASSET_HUB_ID;
+use super::{CustomParaLocation, SiblingParaLocation, ASSET_HUB_ID};
+use crate::ParachainInfo;
 
 use core::marker::PhantomData;
 
 use frame_support::{parameter_types, traits::ContainsPair};
 use sp_runtime::traits::Get;
 use xcm::prelude::*;
+use xcm_filters::{RelayAssetFrom, SiblingNativeAsset};
 
 pub struct NativeAssetFrom<T>(PhantomData<T>);
 impl<T: Get<Location>> ContainsPair<Asset, Location> for NativeAssetFrom<T> {
@@ -16,13 +18,16 @@ impl<T: Get<Location>> ContainsPair<Asset, Location> for NativeAssetFrom<T> {
 	}
 }
 
-pub struct RelayAssetFrom<T>(PhantomData<T>);
-impl<T: Get<Location>> ContainsPair<Asset, Location> for RelayAssetFrom<T> {
+/// The native token of the other instance of this runtime can be teleported to the asset hub.
+/// That makes it a reserve for it as well, but never for our own token.
+pub struct OtherInstanceTokenFrom<T>(PhantomData<T>);
+impl<T: Get<Location>> ContainsPair<Asset, Location> for OtherInstanceTokenFrom<T> {
 	fn contains(asset: &Asset, location: &Location) -> bool {
-		let loc = T::get();
-		&loc == location &&
-			matches!(asset, Asset { id: AssetId(asset_location), fun: Fungible(_) }
-            if *asset_location == Location::parent())
+		let Asset { id: AssetId(asset_location), fun: Fungible(_) } = asset else { return false };
+		let ours = Location::new(1, [Parachain(ParachainInfo::parachain_id().into())]);
+		&T::get() == location &&
+			*asset_location != ours &&
+			[CustomParaLocation::get(), SiblingParaLocation::get()].contains(asset_location)
 	}
 }
 
@@ -30,7 +35,11 @@ parameter_types! {
 	pub AssetHubLocation: Location = Location::new(1, [Parachain(ASSET_HUB_ID)]);
 }
 
-pub type TrustedReserves = RelayAssetFrom<AssetHubLocation>;
+pub type TrustedReserves = (
+	RelayAssetFrom<AssetHubLocation>,
+	SiblingNativeAsset,
+	OtherInstanceTokenFrom<AssetHubLocation>,
+);
 
 /// We only allow teleports of our native asset PARA between here and AssetHub.
 pub type TrustedTeleporters = NativeAssetFrom<AssetHubLocation>;
@@ -10,6 +10,8 @@ pub mod apis;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarks;
 pub mod configs;
+pub mod genesis_config_presets;
+pub mod pallets;
 mod weights;
 
 extern crate alloc;
@@ -296,6 +298,10 @@ mod runtime {
 	pub type CumulusXcm = cumulus_pallet_xcm::Pallet<Runtime>;
 	#[runtime::pallet_index(33)]
 	pub type MessageQueue = pallet_message_queue::Pallet<Runtime>;
+	#[runtime::pallet_index(34)]
+	pub type HrmpChannels = crate::pallets::hrmp_channels::Pallet<Runtime>;
+	#[runtime::pallet_index(35)]
+	pub type XcmFailures = crate::pallets::xcm_failures::Pallet<Runtime>;
 }
 
 cumulus_pallet_parachain_system::register_validate_block! {
@@ -0,0 +1,118 @@
+//! Automatically manages HRMP channels with trusted siblings.
+//!
+//! The relay chain notifies us via XCM whenever a sibling wants to open a channel to us,
+//! accepts one of our requests, or closes a channel.
+//! This pallet handles those notifications in the XCM executor.
+//! When a request comes from a trusted sibling, it accepts the request and opens a
+//! channel back on the relay chain, so no manual governance is needed.
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use alloc::{vec, vec::Vec};
+	use codec::Encode;
+	use cumulus_primitives_core::ParaId;
+	use frame_support::{pallet_prelude::*, traits::Contains};
+	use xcm::latest::prelude::*;
+	use xcm_executor::traits::{
+		HandleHrmpChannelAccepted, HandleHrmpChannelClosing, HandleHrmpNewChannelOpenRequest,
+	};
+
+	/// Index of `hrmp_init_open_channel` in the relay chain's HRMP pallet.
+	///
+	/// The relay chain runtime isn't a dependency, so the indices are copied from the
+	/// `#[pallet::call_index]` attributes of `polkadot_runtime_parachains::hrmp`.
+	const INIT_OPEN_CHANNEL_CALL_INDEX: u8 = 0;
+	/// Index of `hrmp_accept_open_channel` in the relay chain's HRMP pallet.
+	const ACCEPT_OPEN_CHANNEL_CALL_INDEX: u8 = 1;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Siblings whose channel requests are accepted without governance.
+		type TrustedSiblings: Contains<ParaId>;
+		/// The para id of this chain.
+		type SelfParaId: Get<ParaId>;
+		/// How to send the HRMP calls to the relay chain.
+		type XcmSender: SendXcm;
+		/// Index of the HRMP pallet in the relay chain runtime.
+		#[pallet::constant]
+		type RelayHrmpPalletIndex: Get<u8>;
+		/// Fees for executing the HRMP calls on the relay chain.
+		/// They are withdrawn from our sovereign account, so they're expressed
+		/// from the point of view of the relay chain.
+		type RelayFees: Get<Asset>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// We accepted a channel from `sender` and requested a channel back to it.
+		OpenRequestAccepted { sender: ParaId },
+		/// `recipient` accepted our request to open a channel to it.
+		ChannelAccepted { recipient: ParaId },
+		/// `initiator` started closing the channel from `sender` to `recipient`.
+		ChannelClosing { initiator: ParaId, sender: ParaId, recipient: ParaId },
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Dispatches `calls` on the relay chain with our sovereign account as the origin.
+		/// Any fees left over are deposited back into it.
+		fn transact_on_relay(calls: Vec<Vec<u8>>) -> XcmResult {
+			let fees = T::RelayFees::get();
+			let mut message = Xcm::<()>::builder_unsafe()
+				.withdraw_asset(fees.clone())
+				.buy_execution(fees, Unlimited);
+			for call in calls {
+				message = message.transact(OriginKind::Native, None, call);
+			}
+			let message = message
+				.refund_surplus()
+				.deposit_asset(AllCounted(1), Parachain(T::SelfParaId::get().into()))
+				.build();
+			send_xcm::<T::XcmSender>(Location::parent(), message)?;
+			Ok(())
+		}
+	}
+
+	impl<T: Config> HandleHrmpNewChannelOpenRequest for Pallet<T> {
+		fn handle(sender: u32, max_message_size: u32, max_capacity: u32) -> XcmResult {
+			let sender = ParaId::from(sender);
+			if !T::TrustedSiblings::contains(&sender) {
+				// Requests from anyone else are left to governance.
+				return Ok(());
+			}
+			let hrmp = T::RelayHrmpPalletIndex::get();
+			let accept = (hrmp, ACCEPT_OPEN_CHANNEL_CALL_INDEX, sender).encode();
+			let open_back =
+				(hrmp, INIT_OPEN_CHANNEL_CALL_INDEX, sender, max_capacity, max_message_size)
+					.encode();
+			Self::transact_on_relay(vec![accept, open_back])?;
+			Self::deposit_event(Event::OpenRequestAccepted { sender });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> HandleHrmpChannelAccepted for Pallet<T> {
+		fn handle(recipient: u32) -> XcmResult {
+			Self::deposit_event(Event::ChannelAccepted { recipient: recipient.into() });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> HandleHrmpChannelClosing for Pallet<T> {
+		fn handle(initiator: u32, sender: u32, recipient: u32) -> XcmResult {
+			Self::deposit_event(Event::ChannelClosing {
+				initiator: initiator.into(),
+				sender: sender.into(),
+				recipient: recipient.into(),
+			});
+			Ok(())
+		}
+	}
+}
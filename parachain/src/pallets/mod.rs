@@ -0,0 +1,6 @@
+//! Pallets local to this runtime.
+
+// Handles HRMP channel notifications from the relay chain.
+pub mod hrmp_channels;
+// Reports the failing instruction of inbound XCMs.
+pub mod xcm_failures;
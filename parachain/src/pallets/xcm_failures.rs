@@ -0,0 +1,68 @@
+//! Reports exactly where and why an inbound XCM failed.
+//!
+//! The message queue only tells us whether a message was processed successfully.
+//! This pallet wraps the XCM executor used for processing messages and emits an event
+//! with the failing instruction index, the error and the message topic whenever
+//! execution doesn't complete.
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use core::marker::PhantomData;
+	use frame_support::pallet_prelude::*;
+	use xcm::latest::{prelude::*, InstructionError};
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Executing an XCM from `origin` stopped at instruction `index` because of `error`.
+		/// `message_id` is the topic of the message, if it had one, or its hash otherwise.
+		ExecutionFailed { origin: Location, index: u8, error: XcmError, message_id: XcmHash },
+	}
+
+	/// Wraps an XCM `Executor` to emit [`Event::ExecutionFailed`] when execution fails.
+	pub struct EmitFailures<T, Executor>(PhantomData<(T, Executor)>);
+	impl<T: Config, Call, Executor: ExecuteXcm<Call>> ExecuteXcm<Call> for EmitFailures<T, Executor> {
+		type Prepared = Executor::Prepared;
+
+		fn prepare(
+			message: Xcm<Call>,
+			weight_limit: Weight,
+		) -> Result<Self::Prepared, InstructionError> {
+			Executor::prepare(message, weight_limit)
+		}
+
+		fn execute(
+			origin: impl Into<Location>,
+			pre: Self::Prepared,
+			id: &mut XcmHash,
+			weight_credit: Weight,
+		) -> Outcome {
+			let origin = origin.into();
+			let outcome = Executor::execute(origin.clone(), pre, id, weight_credit);
+			if let Outcome::Incomplete { error, .. } | Outcome::Error(error) = &outcome {
+				Pallet::<T>::deposit_event(Event::ExecutionFailed {
+					origin,
+					index: error.index,
+					error: error.error,
+					message_id: *id,
+				});
+			}
+			outcome
+		}
+
+		fn charge_fees(location: impl Into<Location>, fees: Assets) -> XcmResult {
+			Executor::charge_fees(location, fees)
+		}
+	}
+}
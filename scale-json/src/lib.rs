@@ -0,0 +1,98 @@
+//! The JSON form of any type with SCALE type information.
+//!
+//! Both directions walk the [`TypeInfo`] of the type, so the JSON doesn't say which types it
+//! holds, and what is written can always be read back. It doesn't depend on `xcm`, so it works
+//! with the types of any version of it.
+//!
+//! # Schema
+//!
+//! The JSON follows the Rust types:
+//!
+//! - Structs with named fields are objects: `{ "parents": 1, "interior": "Here" }`.
+//! - Structs with unnamed fields are arrays, and wrappers of a single field, like `Assets`, are the
+//!   field itself. Fields that take no space, like `PhantomData`, are left out.
+//! - Enums are written like serde does by default. A variant without fields is its name, `"Here"`,
+//!   and the others are an object of one entry, `{ "Parachain": 1000 }`, whose value follows the
+//!   rules for structs.
+//! - Vectors, arrays and tuples are arrays. `()` is `null`.
+//! - Bytes, in vectors or arrays, are a `0x` prefixed hex string.
+//! - Integers, compact or not, are numbers up to [`MAX_SAFE_INTEGER`] and decimal strings above it,
+//!   since JavaScript can't hold them as numbers. Both forms are read back.
+//! - Booleans and strings are themselves.
+//!
+//! Signed integers, characters and bit sequences aren't supported.
+
+mod walk;
+
+use core::fmt;
+
+use codec::{Decode, Encode};
+use scale_info::{meta_type, PortableRegistry, Registry, TypeInfo};
+use serde_json::Value;
+
+/// The biggest integer a JavaScript number holds exactly, `2^53 - 1`.
+pub const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+#[derive(Debug)]
+pub enum Error {
+	/// The JSON does not have the shape of the type.
+	Json(String),
+	/// The bytes written from the JSON are not a valid value of the type.
+	Decode(codec::Error),
+	/// The value was decoded but some bytes were left.
+	TrailingBytes(usize),
+	/// The type has parts the JSON form doesn't cover, like bit sequences.
+	Unsupported(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Json(error) => write!(f, "invalid JSON: {error}"),
+			Error::Decode(error) => write!(f, "could not decode: {error}"),
+			Error::TrailingBytes(left) => write!(f, "{left} bytes left after decoding"),
+			Error::Unsupported(error) => write!(f, "unsupported type: {error}"),
+		}
+	}
+}
+
+impl From<codec::Error> for Error {
+	fn from(error: codec::Error) -> Self {
+		Error::Decode(error)
+	}
+}
+
+/// The registry with `T` and all the types it's made of, and the id of `T`.
+fn registry<T: TypeInfo + 'static>() -> (PortableRegistry, u32) {
+	let mut registry = Registry::new();
+	let id = registry.register_type(&meta_type::<T>()).id;
+	(registry.into(), id)
+}
+
+/// Decodes all of `bytes` as a `T`.
+pub fn decode_all<T: Decode>(mut bytes: &[u8]) -> Result<T, Error> {
+	let decoded = T::decode(&mut bytes)?;
+	match bytes.len() {
+		0 => Ok(decoded),
+		left => Err(Error::TrailingBytes(left)),
+	}
+}
+
+/// The JSON form of `value`.
+///
+/// Fails if `T` is made of types the JSON form doesn't cover.
+pub fn to_json<T: Encode + TypeInfo + 'static>(value: &T) -> Result<Value, Error> {
+	let (registry, id) = registry::<T>();
+	walk::decode(&registry, id, &mut &value.encode()[..]).map_err(Error::Unsupported)
+}
+
+/// Reads the JSON form written by [`to_json`].
+pub fn from_json<T: Decode + TypeInfo + 'static>(value: &Value) -> Result<T, Error> {
+	let (registry, id) = registry::<T>();
+	let mut bytes = Vec::new();
+	walk::encode(&registry, id, value, &mut bytes).map_err(Error::Json)?;
+	decode_all(&bytes)
+}
+
+#[cfg(test)]
+mod tests;
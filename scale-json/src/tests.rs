@@ -0,0 +1,70 @@
+use codec::{Compact, Decode, Encode};
+use scale_info::TypeInfo;
+use serde_json::json;
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode, TypeInfo)]
+struct Transfer {
+	amount: u128,
+	#[codec(compact)]
+	fee: u128,
+	to: Beneficiary,
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode, TypeInfo)]
+enum Beneficiary {
+	Nobody,
+	Account([u8; 4]),
+	Accounts(Vec<[u8; 4]>, bool),
+}
+
+#[test]
+fn json_follows_the_types() {
+	let transfer = Transfer { amount: 100, fee: 1, to: Beneficiary::Account([1; 4]) };
+	let expected = json!({ "amount": 100, "fee": 1, "to": { "Account": "0x01010101" } });
+	assert_eq!(to_json(&transfer).unwrap(), expected);
+	assert_eq!(from_json::<Transfer>(&expected).unwrap(), transfer);
+
+	let transfer = Transfer { to: Beneficiary::Accounts(vec![[2; 4]], true), ..transfer };
+	let expected = json!({ "amount": 100, "fee": 1, "to": { "Accounts": [["0x02020202"], true] } });
+	assert_eq!(to_json(&transfer).unwrap(), expected);
+	assert_eq!(from_json::<Transfer>(&expected).unwrap(), transfer);
+
+	assert_eq!(to_json(&Beneficiary::Nobody).unwrap(), json!("Nobody"));
+	assert_eq!(to_json(&()).unwrap(), json!(null));
+}
+
+#[test]
+fn numbers_javascript_cant_hold_are_strings() {
+	let safe = MAX_SAFE_INTEGER as u128;
+	assert_eq!(to_json(&safe).unwrap(), json!(9007199254740991u64));
+	assert_eq!(to_json(&(safe + 1)).unwrap(), json!("9007199254740992"));
+	assert_eq!(to_json(&u64::MAX).unwrap(), json!("18446744073709551615"));
+	assert_eq!(to_json(&Compact(u128::MAX)).unwrap(), json!(u128::MAX.to_string()));
+
+	for number in [0, safe, safe + 1, u128::MAX] {
+		assert_eq!(from_json::<u128>(&to_json(&number).unwrap()).unwrap(), number);
+		assert_eq!(
+			from_json::<Compact<u128>>(&to_json(&Compact(number)).unwrap()).unwrap().0,
+			number
+		);
+	}
+	// Either form is read back.
+	assert_eq!(from_json::<u128>(&json!("100")).unwrap(), 100);
+	assert_eq!(from_json::<u128>(&json!(18446744073709551615u64)).unwrap(), u64::MAX as u128);
+}
+
+#[test]
+fn bad_input_is_refused() {
+	assert!(matches!(from_json::<u8>(&json!(256)), Err(Error::Json(_))));
+	assert!(matches!(from_json::<Transfer>(&json!({ "amount": 1 })), Err(Error::Json(_))));
+	assert!(matches!(from_json::<Beneficiary>(&json!("Somebody")), Err(Error::Json(_))));
+	assert!(matches!(decode_all::<u8>(&[1, 2]), Err(Error::TrailingBytes(1))));
+}
+
+#[test]
+fn unsupported_types_are_refused() {
+	assert!(matches!(to_json(&-1i32), Err(Error::Unsupported(_))));
+	assert!(matches!(to_json(&(1u8, -1i64)), Err(Error::Unsupported(_))));
+}
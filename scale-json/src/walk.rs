@@ -0,0 +1,301 @@
+//! Walks a [`PortableRegistry`] to decode SCALE into JSON and encode JSON into SCALE.
+
+use codec::{Compact, Decode, Encode};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::{Map, Value};
+
+type Result<T> = core::result::Result<T, String>;
+
+/// Decodes the value of type `id` from `input` into JSON.
+pub(crate) fn decode(registry: &PortableRegistry, id: u32, input: &mut &[u8]) -> Result<Value> {
+	let ty = resolve(registry, id)?;
+	match &ty.type_def {
+		TypeDef::Composite(composite) => decode_fields(registry, &composite.fields, input),
+		TypeDef::Variant(variant) => {
+			let index = u8::decode(input).map_err(|error| error.to_string())?;
+			let variant = variant
+				.variants
+				.iter()
+				.find(|variant| variant.index == index)
+				.ok_or_else(|| format!("no variant with index {index} in `{}`", ty.path))?;
+			if fields(registry, &variant.fields).next().is_none() {
+				return Ok(Value::String(variant.name.clone()));
+			}
+			let mut object = Map::new();
+			object.insert(variant.name.clone(), decode_fields(registry, &variant.fields, input)?);
+			Ok(Value::Object(object))
+		},
+		TypeDef::Sequence(sequence) => {
+			let Compact(len) = Compact::<u32>::decode(input).map_err(|error| error.to_string())?;
+			decode_items(registry, sequence.type_param.id, len as usize, input)
+		},
+		TypeDef::Array(array) =>
+			decode_items(registry, array.type_param.id, array.len as usize, input),
+		TypeDef::Tuple(tuple) => match &tuple.fields[..] {
+			[] => Ok(Value::Null),
+			fields => fields
+				.iter()
+				.map(|field| decode(registry, field.id, input))
+				.collect::<Result<Vec<_>>>()
+				.map(Value::Array),
+		},
+		TypeDef::Primitive(primitive) => decode_primitive(primitive, input),
+		TypeDef::Compact(_) => {
+			let Compact(number) =
+				Compact::<u128>::decode(input).map_err(|error| error.to_string())?;
+			Ok(number_to_json(number))
+		},
+		TypeDef::BitSequence(_) => Err("bit sequences are not supported".into()),
+	}
+}
+
+/// Encodes the JSON `value` as a value of type `id` into `output`.
+pub(crate) fn encode(
+	registry: &PortableRegistry,
+	id: u32,
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> Result<()> {
+	let ty = resolve(registry, id)?;
+	match &ty.type_def {
+		TypeDef::Composite(composite) => encode_fields(registry, &composite.fields, value, output),
+		TypeDef::Variant(variant) => {
+			let null = Value::Null;
+			let (name, fields) = match value {
+				Value::String(name) => (name, &null),
+				Value::Object(object) if object.len() == 1 =>
+					object.iter().next().expect("the object has one entry"),
+				other => return Err(format!("expected a variant of `{}`, got {other}", ty.path)),
+			};
+			let variant = variant
+				.variants
+				.iter()
+				.find(|variant| &variant.name == name)
+				.ok_or_else(|| format!("no variant `{name}` in `{}`", ty.path))?;
+			output.push(variant.index);
+			encode_fields(registry, &variant.fields, fields, output)
+		},
+		TypeDef::Sequence(sequence) => {
+			let len = items_len(registry, sequence.type_param.id, value)?;
+			Compact(len as u32).encode_to(output);
+			encode_items(registry, sequence.type_param.id, value, output)
+		},
+		TypeDef::Array(array) => {
+			let len = items_len(registry, array.type_param.id, value)?;
+			if len != array.len as usize {
+				return Err(format!("expected {} items, got {len}", array.len));
+			}
+			encode_items(registry, array.type_param.id, value, output)
+		},
+		TypeDef::Tuple(tuple) => match (&tuple.fields[..], value) {
+			([], _) => Ok(()),
+			(fields, Value::Array(values)) if fields.len() == values.len() => fields
+				.iter()
+				.zip(values)
+				.try_for_each(|(field, value)| encode(registry, field.id, value, output)),
+			(_, other) => Err(format!("expected a tuple, got {other}")),
+		},
+		TypeDef::Primitive(primitive) => encode_primitive(primitive, value, output),
+		TypeDef::Compact(_) => {
+			Compact(json_to_number(value)?).encode_to(output);
+			Ok(())
+		},
+		TypeDef::BitSequence(_) => Err("bit sequences are not supported".into()),
+	}
+}
+
+fn resolve(registry: &PortableRegistry, id: u32) -> Result<&scale_info::Type<PortableForm>> {
+	registry.resolve(id).ok_or_else(|| format!("unknown type {id}"))
+}
+
+/// The fields that take space when encoded, others like `PhantomData` are left out.
+fn fields<'a>(
+	registry: &'a PortableRegistry,
+	fields: &'a [Field<PortableForm>],
+) -> impl Iterator<Item = &'a Field<PortableForm>> {
+	fields.iter().filter(move |field| !is_empty(registry, field.ty.id))
+}
+
+fn is_empty(registry: &PortableRegistry, id: u32) -> bool {
+	match registry.resolve(id).map(|ty| &ty.type_def) {
+		Some(TypeDef::Tuple(tuple)) => tuple.fields.is_empty(),
+		Some(TypeDef::Composite(composite)) => composite.fields.is_empty(),
+		_ => false,
+	}
+}
+
+fn is_byte(registry: &PortableRegistry, id: u32) -> bool {
+	matches!(
+		registry.resolve(id).map(|ty| &ty.type_def),
+		Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+	)
+}
+
+/// Named fields are an object, a single unnamed field stands for the whole value and
+/// other unnamed fields are an array.
+fn decode_fields(
+	registry: &PortableRegistry,
+	all_fields: &[Field<PortableForm>],
+	input: &mut &[u8],
+) -> Result<Value> {
+	let fields: Vec<_> = fields(registry, all_fields).collect();
+	match &fields[..] {
+		[] => Ok(Value::Null),
+		[field] if field.name.is_none() => decode(registry, field.ty.id, input),
+		fields if fields.iter().all(|field| field.name.is_some()) => fields
+			.iter()
+			.map(|field| -> Result<(String, Value)> {
+				let name = field.name.clone().expect("all fields are named");
+				Ok((name, decode(registry, field.ty.id, input)?))
+			})
+			.collect::<Result<Map<_, _>>>()
+			.map(Value::Object),
+		fields => fields
+			.iter()
+			.map(|field| decode(registry, field.ty.id, input))
+			.collect::<Result<Vec<_>>>()
+			.map(Value::Array),
+	}
+}
+
+fn encode_fields(
+	registry: &PortableRegistry,
+	all_fields: &[Field<PortableForm>],
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> Result<()> {
+	let fields: Vec<_> = fields(registry, all_fields).collect();
+	match (&fields[..], value) {
+		([], _) => Ok(()),
+		([field], value) if field.name.is_none() => encode(registry, field.ty.id, value, output),
+		(fields, Value::Object(object)) if fields.iter().all(|field| field.name.is_some()) =>
+			fields.iter().try_for_each(|field| {
+				let name = field.name.as_ref().expect("all fields are named");
+				let value = object.get(name).ok_or_else(|| format!("missing field `{name}`"))?;
+				encode(registry, field.ty.id, value, output)
+			}),
+		(fields, Value::Array(values)) if fields.len() == values.len() => fields
+			.iter()
+			.zip(values)
+			.try_for_each(|(field, value)| encode(registry, field.ty.id, value, output)),
+		(fields, other) => Err(format!("expected {} fields, got {other}", fields.len())),
+	}
+}
+
+/// Bytes are a hex string, other items an array.
+fn decode_items(
+	registry: &PortableRegistry,
+	item: u32,
+	len: usize,
+	input: &mut &[u8],
+) -> Result<Value> {
+	if is_byte(registry, item) {
+		if input.len() < len {
+			return Err(format!("expected {len} bytes, got {}", input.len()));
+		}
+		let (bytes, rest) = input.split_at(len);
+		*input = rest;
+		return Ok(Value::String(format!("0x{}", hex::encode(bytes))));
+	}
+	(0..len)
+		.map(|_| decode(registry, item, input))
+		.collect::<Result<Vec<_>>>()
+		.map(Value::Array)
+}
+
+fn items_len(registry: &PortableRegistry, item: u32, value: &Value) -> Result<usize> {
+	match value {
+		Value::String(hex) if is_byte(registry, item) => Ok(bytes(hex)?.len()),
+		Value::Array(values) => Ok(values.len()),
+		other => Err(format!("expected an array or hex string, got {other}")),
+	}
+}
+
+fn encode_items(
+	registry: &PortableRegistry,
+	item: u32,
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> Result<()> {
+	match value {
+		Value::String(hex) => {
+			output.extend(bytes(hex)?);
+			Ok(())
+		},
+		Value::Array(values) =>
+			values.iter().try_for_each(|value| encode(registry, item, value, output)),
+		other => Err(format!("expected an array or hex string, got {other}")),
+	}
+}
+
+fn bytes(hex: &str) -> Result<Vec<u8>> {
+	let hex = hex.trim();
+	hex::decode(hex.strip_prefix("0x").unwrap_or(hex)).map_err(|error| error.to_string())
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Value> {
+	fn number<T: Decode + Into<u128>>(input: &mut &[u8]) -> Result<Value> {
+		let number = T::decode(input).map_err(|error| error.to_string())?;
+		Ok(number_to_json(number.into()))
+	}
+
+	match primitive {
+		TypeDefPrimitive::Bool =>
+			bool::decode(input).map(Value::Bool).map_err(|error| error.to_string()),
+		TypeDefPrimitive::Str =>
+			String::decode(input).map(Value::String).map_err(|error| error.to_string()),
+		TypeDefPrimitive::U8 => number::<u8>(input),
+		TypeDefPrimitive::U16 => number::<u16>(input),
+		TypeDefPrimitive::U32 => number::<u32>(input),
+		TypeDefPrimitive::U64 => number::<u64>(input),
+		TypeDefPrimitive::U128 => number::<u128>(input),
+		other => Err(format!("{other:?} is not supported")),
+	}
+}
+
+fn encode_primitive(
+	primitive: &TypeDefPrimitive,
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> Result<()> {
+	fn number<T: TryFrom<u128> + Encode>(value: &Value, output: &mut Vec<u8>) -> Result<()> {
+		let number = json_to_number(value)?;
+		let number = T::try_from(number).map_err(|_| format!("{number} is out of range"))?;
+		number.encode_to(output);
+		Ok(())
+	}
+
+	match (primitive, value) {
+		(TypeDefPrimitive::Bool, Value::Bool(value)) => {
+			value.encode_to(output);
+			Ok(())
+		},
+		(TypeDefPrimitive::Str, Value::String(value)) => {
+			value.encode_to(output);
+			Ok(())
+		},
+		(TypeDefPrimitive::U8, value) => number::<u8>(value, output),
+		(TypeDefPrimitive::U16, value) => number::<u16>(value, output),
+		(TypeDefPrimitive::U32, value) => number::<u32>(value, output),
+		(TypeDefPrimitive::U64, value) => number::<u64>(value, output),
+		(TypeDefPrimitive::U128, value) => number::<u128>(value, output),
+		(other, value) => Err(format!("can't encode {value} as {other:?}")),
+	}
+}
+
+/// Numbers JavaScript can't hold exactly are strings.
+fn number_to_json(number: u128) -> Value {
+	match u64::try_from(number) {
+		Ok(number) if number <= crate::MAX_SAFE_INTEGER => number.into(),
+		_ => number.to_string().into(),
+	}
+}
+
+fn json_to_number(value: &Value) -> Result<u128> {
+	match value {
+		Value::Number(number) =>
+			number.as_u64().map(Into::into).ok_or_else(|| format!("{number} is not a u64")),
+		Value::String(number) => number.parse().map_err(|_| format!("{number} is not a u128")),
+		other => Err(format!("expected a number, got {other}")),
+	}
+}
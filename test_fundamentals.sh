@@ -6,6 +6,10 @@ features=(
     "location"
     "asset"
     "instruction"
+    "versions"
+    "lint"
+    "interpreter"
+    "json"
     "holding"
 	"xcm_executor"
 	"pallet_xcm"
@@ -0,0 +1,213 @@
+//! Decodes and encodes the versioned XCM types.
+//!
+//! Block explorers show messages, locations and assets as SCALE encoded hex.
+//! This crate reads them back into `VersionedXcm`, `VersionedLocation` and `VersionedAssets`,
+//! prints them like the executor's trace logs and converts them to and from a JSON form.
+//!
+//! The JSON form is written by `scale-json`, whose docs describe it.
+
+use core::{fmt, str::FromStr};
+
+use codec::Encode;
+use scale_json::{decode_all, from_json, to_json};
+use xcm::{VersionedAssets, VersionedLocation, VersionedXcm};
+
+/// The types we know how to read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+	Xcm,
+	Location,
+	Assets,
+}
+
+impl FromStr for Kind {
+	type Err = String;
+
+	fn from_str(kind: &str) -> Result<Self, Self::Err> {
+		match kind {
+			"xcm" => Ok(Kind::Xcm),
+			"location" => Ok(Kind::Location),
+			"assets" => Ok(Kind::Assets),
+			other => Err(format!("unknown kind `{other}`, expected xcm, location or assets")),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum Error {
+	/// The input is not hex.
+	Hex(hex::FromHexError),
+	/// The bytes are not a SCALE encoded value of the given kind.
+	Decode(codec::Error),
+	/// The value was decoded but some bytes were left.
+	TrailingBytes(usize),
+	/// The JSON does not have the shape of the given kind.
+	Json(String),
+	/// The type has parts the JSON form doesn't cover.
+	Unsupported(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Hex(error) => write!(f, "invalid hex: {error}"),
+			Error::Decode(error) => write!(f, "could not decode: {error}"),
+			Error::TrailingBytes(left) => write!(f, "{left} bytes left after decoding"),
+			Error::Json(error) => write!(f, "invalid JSON: {error}"),
+			Error::Unsupported(error) => write!(f, "unsupported type: {error}"),
+		}
+	}
+}
+
+impl From<hex::FromHexError> for Error {
+	fn from(error: hex::FromHexError) -> Self {
+		Error::Hex(error)
+	}
+}
+
+impl From<scale_json::Error> for Error {
+	fn from(error: scale_json::Error) -> Self {
+		match error {
+			scale_json::Error::Json(error) => Error::Json(error),
+			scale_json::Error::Decode(error) => Error::Decode(error),
+			scale_json::Error::TrailingBytes(left) => Error::TrailingBytes(left),
+			scale_json::Error::Unsupported(error) => Error::Unsupported(error),
+		}
+	}
+}
+
+/// Reads hex, with or without the `0x` prefix and surrounding whitespace.
+pub fn parse_hex(input: &str) -> Result<Vec<u8>, Error> {
+	let input = input.trim();
+	Ok(hex::decode(input.strip_prefix("0x").unwrap_or(input))?)
+}
+
+/// A decoded value of any of the versions we support: v3, v4 and v5.
+///
+/// Messages are decoded with `()` as their call type, since `Transact` keeps its call encoded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decoded {
+	Xcm(VersionedXcm<()>),
+	Location(VersionedLocation),
+	Assets(VersionedAssets),
+}
+
+impl From<VersionedXcm<()>> for Decoded {
+	fn from(xcm: VersionedXcm<()>) -> Self {
+		Decoded::Xcm(xcm)
+	}
+}
+
+impl From<VersionedLocation> for Decoded {
+	fn from(location: VersionedLocation) -> Self {
+		Decoded::Location(location)
+	}
+}
+
+impl From<VersionedAssets> for Decoded {
+	fn from(assets: VersionedAssets) -> Self {
+		Decoded::Assets(assets)
+	}
+}
+
+impl Decoded {
+	/// Decodes all of `bytes` as a value of `kind`.
+	pub fn decode(kind: Kind, bytes: &[u8]) -> Result<Self, Error> {
+		Ok(match kind {
+			Kind::Xcm => Decoded::Xcm(decode_all(bytes)?),
+			Kind::Location => Decoded::Location(decode_all(bytes)?),
+			Kind::Assets => Decoded::Assets(decode_all(bytes)?),
+		})
+	}
+
+	/// Reads the JSON form written by [`Decoded::to_json`].
+	pub fn from_json(kind: Kind, value: &serde_json::Value) -> Result<Self, Error> {
+		Ok(match kind {
+			Kind::Xcm => Decoded::Xcm(from_json(value)?),
+			Kind::Location => Decoded::Location(from_json(value)?),
+			Kind::Assets => Decoded::Assets(from_json(value)?),
+		})
+	}
+
+	pub fn kind(&self) -> Kind {
+		match self {
+			Decoded::Xcm(_) => Kind::Xcm,
+			Decoded::Location(_) => Kind::Location,
+			Decoded::Assets(_) => Kind::Assets,
+		}
+	}
+
+	pub fn version(&self) -> u32 {
+		match self {
+			Decoded::Xcm(VersionedXcm::V3(_)) |
+			Decoded::Location(VersionedLocation::V3(_)) |
+			Decoded::Assets(VersionedAssets::V3(_)) => 3,
+			Decoded::Xcm(VersionedXcm::V4(_)) |
+			Decoded::Location(VersionedLocation::V4(_)) |
+			Decoded::Assets(VersionedAssets::V4(_)) => 4,
+			Decoded::Xcm(VersionedXcm::V5(_)) |
+			Decoded::Location(VersionedLocation::V5(_)) |
+			Decoded::Assets(VersionedAssets::V5(_)) => 5,
+		}
+	}
+
+	pub fn encode(&self) -> Vec<u8> {
+		match self {
+			Decoded::Xcm(xcm) => xcm.encode(),
+			Decoded::Location(location) => location.encode(),
+			Decoded::Assets(assets) => assets.encode(),
+		}
+	}
+
+	/// The JSON form, which follows the Rust types as described by `scale-json`.
+	///
+	/// Enums are written like serde does by default, so the version is the outer tag:
+	/// `{ "V5": { "parents": 1, "interior": "Here" } }`.
+	pub fn to_json(&self) -> serde_json::Value {
+		let json = match self {
+			Decoded::Xcm(xcm) => to_json(xcm),
+			Decoded::Location(location) => to_json(location),
+			Decoded::Assets(assets) => to_json(assets),
+		};
+		json.expect("the JSON form covers every XCM type")
+	}
+}
+
+/// Prints the value like the `xcm::process_instruction` trace logs,
+/// with one instruction per line.
+impl fmt::Display for Decoded {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Decoded::Xcm(xcm) => {
+				write!(f, "VersionedXcm::V{}", self.version())?;
+				match xcm {
+					VersionedXcm::V3(xcm) =>
+						xcm.0.iter().try_for_each(|instruction| write!(f, "\n=== {instruction:?}")),
+					VersionedXcm::V4(xcm) =>
+						xcm.0.iter().try_for_each(|instruction| write!(f, "\n=== {instruction:?}")),
+					VersionedXcm::V5(xcm) =>
+						xcm.0.iter().try_for_each(|instruction| write!(f, "\n=== {instruction:?}")),
+				}
+			},
+			Decoded::Location(location) => {
+				writeln!(f, "VersionedLocation::V{}", self.version())?;
+				match location {
+					VersionedLocation::V3(location) => write!(f, "{location:?}"),
+					VersionedLocation::V4(location) => write!(f, "{location:?}"),
+					VersionedLocation::V5(location) => write!(f, "{location:?}"),
+				}
+			},
+			Decoded::Assets(assets) => {
+				writeln!(f, "VersionedAssets::V{}", self.version())?;
+				match assets {
+					VersionedAssets::V3(assets) => write!(f, "{assets:?}"),
+					VersionedAssets::V4(assets) => write!(f, "{assets:?}"),
+					VersionedAssets::V5(assets) => write!(f, "{assets:?}"),
+				}
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests;
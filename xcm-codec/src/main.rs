@@ -0,0 +1,98 @@
+//! Reads SCALE encoded XCM types from hex and writes them back.
+//!
+//! ```sh
+//! cargo run -p xcm-codec -- decode xcm 0x05040a
+//! cargo run -p xcm-codec -- decode location --json 0x050100
+//! cargo run -p xcm-codec -- decode xcm --raw < message.bin
+//! cargo run -p xcm-codec -- encode location '{ "V5": { "parents": 1, "interior": "Here" } }'
+//! ```
+
+use std::{
+	io::{self, Read},
+	process::ExitCode,
+};
+
+use clap::{Parser, Subcommand};
+use xcm_codec::{parse_hex, Decoded, Error, Kind};
+
+#[derive(Parser)]
+#[command(about = "Decodes and encodes versioned XCM messages, locations and assets")]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Decodes SCALE encoded bytes and prints them.
+	Decode {
+		/// What the bytes are: `xcm`, `location` or `assets`.
+		kind: Kind,
+		/// The bytes as hex. They're read from stdin when left out.
+		input: Option<String>,
+		/// Read raw bytes from stdin instead of hex.
+		#[arg(long, conflicts_with = "input")]
+		raw: bool,
+		/// Print the JSON form instead of the trace form.
+		#[arg(long)]
+		json: bool,
+	},
+	/// Encodes the JSON form and prints it as hex.
+	Encode {
+		/// What the JSON is: `xcm`, `location` or `assets`.
+		kind: Kind,
+		/// The JSON. It's read from stdin when left out.
+		input: Option<String>,
+	},
+}
+
+fn main() -> ExitCode {
+	match run(Cli::parse().command) {
+		Ok(output) => {
+			println!("{output}");
+			ExitCode::SUCCESS
+		},
+		Err(error) => {
+			eprintln!("{error}");
+			ExitCode::FAILURE
+		},
+	}
+}
+
+fn run(command: Command) -> Result<String, String> {
+	match command {
+		Command::Decode { kind, input, raw, json } => {
+			let bytes = match (input, raw) {
+				(_, true) => stdin()?,
+				(Some(hex), false) => parse_hex(&hex).map_err(|error| error.to_string())?,
+				(None, false) => parse_hex(&stdin_string()?).map_err(|error| error.to_string())?,
+			};
+			let decoded = Decoded::decode(kind, &bytes).map_err(|error| error.to_string())?;
+			if json {
+				serde_json::to_string_pretty(&decoded.to_json()).map_err(|error| error.to_string())
+			} else {
+				Ok(decoded.to_string())
+			}
+		},
+		Command::Encode { kind, input } => {
+			let input = match input {
+				Some(input) => input,
+				None => stdin_string()?,
+			};
+			let value = serde_json::from_str(&input)
+				.map_err(|error| Error::Json(error.to_string()).to_string())?;
+			let decoded = Decoded::from_json(kind, &value).map_err(|error| error.to_string())?;
+			Ok(format!("0x{}", hex::encode(decoded.encode())))
+		},
+	}
+}
+
+fn stdin() -> Result<Vec<u8>, String> {
+	let mut bytes = Vec::new();
+	io::stdin().read_to_end(&mut bytes).map_err(|error| error.to_string())?;
+	Ok(bytes)
+}
+
+fn stdin_string() -> Result<String, String> {
+	String::from_utf8(stdin()?).map_err(|error| error.to_string())
+}
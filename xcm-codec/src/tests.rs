@@ -0,0 +1,105 @@
+use codec::Encode;
+use fundamentals::instruction::*;
+use fundamentals_xcm::IntoVersion as _;
+use serde_json::json;
+use xcm::{prelude::*, IntoVersion as _};
+
+use super::*;
+
+/// Decodes `bytes` and checks the same bytes come back from the decoded value,
+/// from its hex and from its JSON form.
+fn assert_round_trips(kind: Kind, bytes: &[u8]) -> Decoded {
+	let decoded = Decoded::decode(kind, bytes).unwrap();
+	assert_eq!(decoded.encode(), bytes);
+
+	let hex = format!("0x{}", hex::encode(bytes));
+	assert_eq!(Decoded::decode(kind, &parse_hex(&hex).unwrap()).unwrap(), decoded);
+
+	let json = serde_json::to_string_pretty(&decoded.to_json()).unwrap();
+	let from_json = Decoded::from_json(kind, &serde_json::from_str(&json).unwrap()).unwrap();
+	assert_eq!(from_json, decoded, "{json}");
+	assert_eq!(from_json.encode(), bytes);
+
+	decoded
+}
+
+#[test]
+fn lesson_programs_round_trip() {
+	let programs = [
+		clear_origin_message(),
+		withdraw_asset(),
+		withdraw_and_deposit(),
+		withdraw_and_deposit_paying_fees(),
+	];
+
+	for program in programs {
+		let program = fundamentals_xcm::VersionedXcm::from(program);
+		for version in 3..=5 {
+			// Not every program can be written in older versions, `PayFees` is new in v5.
+			let Ok(program) = program.clone().into_version(version) else { continue };
+			let decoded = assert_round_trips(Kind::Xcm, &program.encode());
+			assert_eq!(decoded.version(), version);
+		}
+	}
+}
+
+#[test]
+fn locations_and_assets_round_trip() {
+	let location = Location::new(1, [Parachain(1000), AccountId32 { network: None, id: [1; 32] }]);
+	let assets: Assets =
+		vec![(Parent, 100u128).into(), ((Parent, Parachain(1000)), 10u128).into()].into();
+
+	for version in 3..=5 {
+		let versioned = VersionedLocation::from(location.clone()).into_version(version).unwrap();
+		assert_eq!(assert_round_trips(Kind::Location, &versioned.encode()).version(), version);
+
+		let versioned = VersionedAssets::from(assets.clone()).into_version(version).unwrap();
+		assert_eq!(assert_round_trips(Kind::Assets, &versioned.encode()).version(), version);
+	}
+}
+
+#[test]
+fn prints_like_the_trace_logs() {
+	let bytes = parse_hex("0x05040a").unwrap();
+	assert_eq!(bytes, VersionedXcm::from(Xcm::<()>(vec![ClearOrigin])).encode());
+	let decoded = Decoded::decode(Kind::Xcm, &bytes).unwrap();
+	assert_eq!(decoded.to_string(), "VersionedXcm::V5\n=== ClearOrigin");
+
+	let decoded = Decoded::from(VersionedLocation::from(Location::new(1, [Parachain(1000)])));
+	assert_eq!(
+		decoded.to_string(),
+		"VersionedLocation::V5\nLocation { parents: 1, interior: X1([Parachain(1000)]) }"
+	);
+}
+
+#[test]
+fn json_follows_the_types() {
+	let location = Location::new(1, [Parachain(1000), AccountId32 { network: None, id: [1; 32] }]);
+	let decoded = Decoded::from(VersionedLocation::from(location));
+
+	let id = format!("0x{}", "01".repeat(32));
+	let expected = json!({
+		"V5": {
+			"parents": 1,
+			"interior": {
+				"X2": [
+					{ "Parachain": 1000 },
+					{ "AccountId32": { "network": "None", "id": id } },
+				],
+			},
+		},
+	});
+	assert_eq!(decoded.to_json(), expected);
+	assert_eq!(Decoded::from_json(Kind::Location, &expected).unwrap(), decoded);
+}
+
+#[test]
+fn bad_input_is_refused() {
+	assert!(matches!(parse_hex("0xzz"), Err(Error::Hex(_))));
+	assert!(matches!(Decoded::decode(Kind::Xcm, &[0xff]), Err(Error::Decode(_))));
+	assert!(matches!(Decoded::decode(Kind::Location, &[5, 1, 0, 0]), Err(Error::TrailingBytes(1))));
+	assert!(matches!(
+		Decoded::from_json(Kind::Location, &json!({ "V5": { "parents": 1 } })),
+		Err(Error::Json(_))
+	));
+}
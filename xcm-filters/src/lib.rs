@@ -0,0 +1,65 @@
+//! XCM filters shared by the parachain runtimes.
+//!
+//! Each runtime still decides which of its calls are safe to `Transact`, since that depends on
+//! the pallets it has, and which reserves it trusts besides the ones here.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+use frame_support::traits::{Contains, ContainsPair, Get};
+use xcm::latest::prelude::*;
+
+/// Programs that local accounts are allowed to run via `PolkadotXcm::execute`.
+///
+/// Only plain accounts on this chain can execute, and only with instructions that move
+/// their own assets around. Instructions that mint assets or claim a different origin
+/// are left to inbound messages, where the barrier and the trust settings apply.
+/// Accounts are `AccountId32` or `AccountKey20` junctions, depending on the runtime.
+///
+/// Only top-level instructions are checked. The ones holding local programs, `SetAppendix`,
+/// `SetErrorHandler` and `ExecuteWithOrigin`, aren't allowed, so nothing runs here unchecked.
+/// The programs carried by transfers run on the destination, under its own filters.
+pub struct XcmExecuteFilter<Call>(PhantomData<Call>);
+impl<Call> Contains<(Location, Xcm<Call>)> for XcmExecuteFilter<Call> {
+	fn contains((origin, message): &(Location, Xcm<Call>)) -> bool {
+		matches!(origin.unpack(), (0, [AccountId32 { .. } | AccountKey20 { .. }])) &&
+			message.inner().iter().all(|instruction| {
+				matches!(
+					instruction,
+					WithdrawAsset(..) |
+						PayFees { .. } | BuyExecution { .. } |
+						RefundSurplus | SetFeesMode { .. } |
+						DepositAsset { .. } | DepositReserveAsset { .. } |
+						TransferAsset { .. } | TransferReserveAsset { .. } |
+						InitiateReserveWithdraw { .. } |
+						InitiateTeleport { .. } | InitiateTransfer { .. } |
+						ExchangeAsset { .. } | ClaimAsset { .. } |
+						// `Transact` is still subject to the `SafeCallFilter`.
+						Transact { .. } | ClearOrigin |
+						ExpectAsset(..) | ExpectOrigin(..) |
+						ExpectError(..) | ExpectTransactStatus(..) |
+						SetTopic(..) | ClearTopic
+				)
+			})
+	}
+}
+
+/// The relay chain token, when it comes from `T`.
+pub struct RelayAssetFrom<T>(PhantomData<T>);
+impl<T: Get<Location>> ContainsPair<Asset, Location> for RelayAssetFrom<T> {
+	fn contains(asset: &Asset, location: &Location) -> bool {
+		&T::get() == location &&
+			matches!(asset, Asset { id: AssetId(asset_location), fun: Fungible(_) }
+				if *asset_location == Location::parent())
+	}
+}
+
+/// A sibling parachain is always a reserve for its own native token.
+pub struct SiblingNativeAsset;
+impl ContainsPair<Asset, Location> for SiblingNativeAsset {
+	fn contains(asset: &Asset, location: &Location) -> bool {
+		matches!(location.unpack(), (1, [Parachain(_)])) &&
+			matches!(asset, Asset { id: AssetId(asset_location), fun: Fungible(_) }
+				if asset_location == location)
+	}
+}